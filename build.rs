@@ -0,0 +1,14 @@
+use compound_io::CompoundMetadata;
+use gmeta::Metadata;
+use std::fs;
+
+fn main() {
+    // метаданные кладутся рядом с собранным контрактом, их загружают в Gear IDEA вместе с ним
+    if let Some((_, opt_wasm)) = gear_wasm_builder::build() {
+        fs::write(
+            opt_wasm.with_file_name("compound.meta.txt"),
+            CompoundMetadata::repr().hex(),
+        )
+        .expect("Unable to write metadata");
+    }
+}
@@ -0,0 +1,148 @@
+// действия администратора: новые рынки и параметры риска меняются только в пределах из `asserts`
+// и только через очередь с задержкой (`timelock`), права администратора передаются в два шага,
+// чтобы не отдать их на неверный адрес
+
+use crate::{asserts, Compound};
+use compound_io::*;
+use gstd::ActorId;
+
+impl Compound {
+    pub fn only_admin(&self, caller: ActorId) -> Result<(), CompoundError> {
+        if caller != self.admin {
+            return Err(CompoundError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    // изменение в пределах и для открытого рынка, проверяется и при постановке в очередь, и при применении
+    pub fn check_change(&self, change: &ParameterChange) -> Result<(), CompoundError> {
+        match change {
+            ParameterChange::AddMarket(config) => self.pool.check_new_market(config),
+            ParameterChange::CollateralFactor {
+                market,
+                collateral_factor,
+            } => {
+                self.pool.market(market)?;
+                asserts::collateral_factor(*collateral_factor)
+            }
+            ParameterChange::RateModel { market, rate_model } => {
+                self.pool.market(market)?;
+                asserts::rate_model(rate_model)
+            }
+            ParameterChange::ReserveFactor {
+                market,
+                reserve_factor,
+            } => {
+                self.pool.market(market)?;
+                asserts::reserve_factor(*reserve_factor)
+            }
+            ParameterChange::SupplyCap { market, .. }
+            | ParameterChange::BorrowCap { market, .. } => self.pool.market(market).map(|_| ()),
+            ParameterChange::CloseFactor { close_factor } => asserts::close_factor(*close_factor),
+            ParameterChange::LiquidationIncentive {
+                liquidation_incentive,
+            } => asserts::liquidation_incentive(*liquidation_incentive),
+            ParameterChange::TimelockDelay { delay } => asserts::timelock_delay(*delay),
+            ParameterChange::PauseGuardian { .. } => Ok(()),
+        }
+    }
+
+    pub fn apply_change(
+        &mut self,
+        change: ParameterChange,
+        now: u64,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.check_change(&change)?;
+        // проценты до изменения начисляются по старым параметрам
+        self.pool.accrue_interest(now);
+
+        match change {
+            ParameterChange::AddMarket(config) => {
+                let market = config.token_address;
+                self.pool.list_market(config, now)?;
+                Ok(CompoundEvent::MarketAdded { market })
+            }
+            ParameterChange::CollateralFactor {
+                market,
+                collateral_factor,
+            } => {
+                self.pool.market_mut(&market)?.collateral_factor = collateral_factor;
+                Ok(CompoundEvent::CollateralFactorSet {
+                    market,
+                    collateral_factor,
+                })
+            }
+            ParameterChange::RateModel { market, rate_model } => {
+                self.pool.market_mut(&market)?.rate_model = rate_model.clone();
+                Ok(CompoundEvent::RateModelSet { market, rate_model })
+            }
+            ParameterChange::ReserveFactor {
+                market,
+                reserve_factor,
+            } => {
+                self.pool.market_mut(&market)?.reserve_factor = reserve_factor;
+                Ok(CompoundEvent::ReserveFactorSet {
+                    market,
+                    reserve_factor,
+                })
+            }
+            ParameterChange::SupplyCap { market, supply_cap } => {
+                self.pool.market_mut(&market)?.supply_cap = supply_cap;
+                Ok(CompoundEvent::SupplyCapSet { market, supply_cap })
+            }
+            ParameterChange::BorrowCap { market, borrow_cap } => {
+                self.pool.market_mut(&market)?.borrow_cap = borrow_cap;
+                Ok(CompoundEvent::BorrowCapSet { market, borrow_cap })
+            }
+            ParameterChange::CloseFactor { close_factor } => {
+                self.pool.close_factor = close_factor;
+                Ok(CompoundEvent::CloseFactorSet { close_factor })
+            }
+            ParameterChange::LiquidationIncentive {
+                liquidation_incentive,
+            } => {
+                self.pool.liquidation_incentive = liquidation_incentive;
+                Ok(CompoundEvent::LiquidationIncentiveSet {
+                    liquidation_incentive,
+                })
+            }
+            ParameterChange::TimelockDelay { delay } => {
+                self.timelock.delay = delay;
+                Ok(CompoundEvent::TimelockDelaySet { delay })
+            }
+            ParameterChange::PauseGuardian { guardian } => {
+                self.pause_guardian = guardian;
+                Ok(CompoundEvent::PauseGuardianSet { guardian })
+            }
+        }
+    }
+
+    pub fn transfer_admin(
+        &mut self,
+        caller: ActorId,
+        new_admin: ActorId,
+    ) -> Result<CompoundEvent, CompoundError> {
+        // права перейдут, только когда новый администратор сам их примет
+        self.only_admin(caller)?;
+        asserts::not_zero_address(&new_admin)?;
+        self.pending_admin = Some(new_admin);
+
+        Ok(CompoundEvent::AdminTransferProposed {
+            pending_admin: new_admin,
+        })
+    }
+
+    pub fn accept_admin(&mut self, caller: ActorId) -> Result<CompoundEvent, CompoundError> {
+        if self.pending_admin != Some(caller) {
+            return Err(CompoundError::Unauthorized);
+        }
+        let old_admin = self.admin;
+        self.admin = caller;
+        self.pending_admin = None;
+
+        Ok(CompoundEvent::AdminTransferred {
+            old_admin,
+            new_admin: caller,
+        })
+    }
+}
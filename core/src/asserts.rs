@@ -0,0 +1,60 @@
+// проверки входных данных, при нарушении действие возвращает ошибку
+
+use compound_io::*;
+use gstd::ActorId;
+
+pub fn greater_zero(value: u128) -> Result<(), CompoundError> {
+    if value == 0 {
+        return Err(CompoundError::ZeroAmount);
+    }
+    Ok(())
+}
+
+pub fn not_zero_address(address: &ActorId) -> Result<(), CompoundError> {
+    if address.is_zero() {
+        return Err(CompoundError::ZeroAddress);
+    }
+    Ok(())
+}
+
+pub fn collateral_factor(value: Wad) -> Result<(), CompoundError> {
+    if value.is_zero() || value > MAX_COLLATERAL_FACTOR {
+        return Err(CompoundError::InvalidCollateralFactor);
+    }
+    Ok(())
+}
+
+pub fn reserve_factor(value: Wad) -> Result<(), CompoundError> {
+    if value > MAX_RESERVE_FACTOR {
+        return Err(CompoundError::InvalidReserveFactor);
+    }
+    Ok(())
+}
+
+pub fn close_factor(value: Wad) -> Result<(), CompoundError> {
+    if value.is_zero() || value > Wad::ONE {
+        return Err(CompoundError::InvalidCloseFactor);
+    }
+    Ok(())
+}
+
+pub fn liquidation_incentive(value: Wad) -> Result<(), CompoundError> {
+    if value > MAX_LIQUIDATION_INCENTIVE {
+        return Err(CompoundError::InvalidLiquidationIncentive);
+    }
+    Ok(())
+}
+
+pub fn rate_model(model: &RateModel) -> Result<(), CompoundError> {
+    if !model.is_valid() {
+        return Err(CompoundError::InvalidRateModel);
+    }
+    Ok(())
+}
+
+pub fn timelock_delay(delay: u64) -> Result<(), CompoundError> {
+    if !(timelock::MIN_TIMELOCK_DELAY..=timelock::MAX_TIMELOCK_DELAY).contains(&delay) {
+        return Err(CompoundError::InvalidTimelockDelay);
+    }
+    Ok(())
+}
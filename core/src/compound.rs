@@ -0,0 +1,401 @@
+// весь протокол как детерминированный автомат: `apply` получает действие, его автора и время
+// и отвечает шагом `Step` - итоговым событием или эффектом, который выполняет адаптер: запросом
+// цен у оракула, переводом токенов или созданием контракта ctoken. Результат эффекта возвращается
+// в `prices_fetched`, `transferred` или `ctoken_created`, поэтому автомат ничего не ждет сам и одинаково работает в контракте, в тестах
+// и на хосте
+
+use crate::{asserts, oracle::PriceOracle, timelock::Timelock, Pool};
+use compound_io::{ft::FTAction, *};
+use gstd::{
+    collections::{BTreeMap, BTreeSet},
+    prelude::*,
+    ActorId, CodeId,
+};
+
+#[derive(Debug, Default, Clone)]
+pub struct Compound {
+    pub admin: ActorId, // кто может открывать рынки и менять параметры риска
+    pub pending_admin: Option<ActorId>, // предложенный администратор, который еще не принял права
+    pub pause_guardian: ActorId, // кто может приостанавливать действия в рынках
+    pub oracle: PriceOracle, // откуда берутся цены токенов для сравнения залога и долгов
+    pub timelock: Timelock, // очередь изменений параметров риска
+    pub init_time: u64, // время инициализации контракта
+    pub program_id: ActorId, // адрес контракта, на котором лежат токены пула
+    pub ctoken_code: CodeId, // код, из которого создаются контракты ctokens рынков
+    pub pool: Pool,     // рынки, параметры ликвидации и весь учет вкладов и кредитов
+    pub transactions: BTreeMap<u64, Transaction>, // операции, чей перевод еще не подтвержден
+    pub next_tx_id: u64, // id следующей операции
+    pub locked_accounts: BTreeSet<ActorId>, // счета, по которым сейчас выполняется действие
+}
+
+// следующий шаг действия
+#[derive(Debug, Clone)]
+pub enum Step {
+    // действие завершено
+    Done(CompoundEvent),
+    // нужны цены оракула для токенов всех рынков, ответы передаются в `Compound::prices_fetched`
+    // вместе с действием, которое после этого выполняется
+    FetchPrices {
+        oracle: ActorId,
+        tokens: Vec<ActorId>,
+        action: CompoundAction,
+        caller: ActorId,
+    },
+    // нужен перевод токенов, его результат передается в `Compound::transferred`
+    Transfer {
+        transfer: Transfer,
+        pending: Pending,
+    },
+    // открыт рынок, для него нужно создать контракт ctoken из кода `code`; адрес контракта
+    // передается в `Compound::ctoken_created`
+    CreateCToken {
+        code: CodeId,
+        market: ActorId,
+    },
+}
+
+// что сделать, когда перевод выполнен или не прошел
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pending {
+    // перевод операции из журнала, после которого она применяется к таблицам
+    Transaction(u64),
+    // пополнение резервов администратором
+    AddReserves { market: ActorId, amount: Tokens },
+    // вывод резервов, которые уже списаны и возвращаются, если перевод не прошел
+    ReduceReserves { market: ActorId, amount: Tokens },
+}
+
+impl Compound {
+    pub fn new(
+        config: CompoundInit,
+        admin: ActorId,
+        program_id: ActorId,
+        now: u64,
+    ) -> Result<Self, CompoundError> {
+        let pool = Pool::new(config.close_factor, config.liquidation_incentive)?;
+        asserts::timelock_delay(config.timelock_delay)?;
+
+        let mut compound = Self {
+            admin,
+            pause_guardian: config.pause_guardian,
+            oracle: PriceOracle {
+                address: config.oracle,
+                max_price_age: config.max_price_age,
+            },
+            timelock: Timelock {
+                delay: config.timelock_delay,
+                ..Default::default()
+            },
+            init_time: now,
+            program_id,
+            ctoken_code: config.ctoken_code,
+            pool,
+            ..Default::default()
+        };
+        for market in config.markets {
+            compound.pool.list_market(market, now)?;
+        }
+        Ok(compound)
+    }
+
+    pub fn apply(
+        &mut self,
+        action: CompoundAction,
+        caller: ActorId,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        // действия ctokens принимаются только от контрактов ctokens, чужое отклоняется сразу,
+        // до запроса цен
+        if matches!(action, CompoundAction::CToken { .. }) {
+            self.ctoken_market(caller)?;
+        }
+        // перед проверками залога запрашиваем у оракула цены всех рынков
+        if matches!(
+            action,
+            CompoundAction::BorrowTokens { .. }
+                | CompoundAction::WithdrawTokens { .. }
+                | CompoundAction::Liquidate { .. }
+                | CompoundAction::ExitMarket { .. }
+                | CompoundAction::CToken {
+                    action: FTAction::Transfer { .. },
+                    ..
+                }
+        ) {
+            let tokens: Vec<_> = self.pool.markets.keys().copied().collect();
+            if self.oracle.address.is_zero() {
+                // оракула нет - сразу берем резервные цены
+                let prices = tokens.into_iter().map(|token| (token, None)).collect();
+                return self.prices_fetched(action, caller, prices, now);
+            }
+            return Ok(Step::FetchPrices {
+                oracle: self.oracle.address,
+                tokens,
+                action,
+                caller,
+            });
+        }
+        self.run(action, caller, now)
+    }
+
+    // ответы оракула по токенам: цена и время ее обновления, `None` - оракул не ответил
+    pub fn prices_fetched(
+        &mut self,
+        action: CompoundAction,
+        caller: ActorId,
+        prices: Vec<(ActorId, Option<(Wad, u64)>)>,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        for (token, fetched) in prices {
+            let fallback_price = self.pool.market(&token)?.fallback_price;
+            let price = self.oracle.price(token, fetched, fallback_price, now)?;
+            self.pool.set_price(&token, price, now)?;
+        }
+        self.run(action, caller, now)
+    }
+
+    pub fn transferred(
+        &mut self,
+        pending: Pending,
+        result: Result<(), CompoundError>,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        match pending {
+            Pending::Transaction(tx_id) => self.transaction_transferred(tx_id, result, now),
+            Pending::AddReserves { market, amount } => {
+                result?;
+                self.reserves_added(market, amount).map(Step::Done)
+            }
+            Pending::ReduceReserves { market, amount } => self
+                .reserves_reduced(market, amount, result)
+                .map(Step::Done),
+        }
+    }
+
+    fn run(
+        &mut self,
+        action: CompoundAction,
+        caller: ActorId,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        match action {
+            CompoundAction::LendTokens { market, amount } => {
+                self.pool.accrue_interest(now);
+                let operation = self.pool.lend(market, amount)?;
+                self.run_operation(caller, operation)
+            }
+            CompoundAction::BorrowTokens { market, amount } => {
+                self.pool.accrue_interest(now);
+                let operation = self.pool.borrow(caller, market, amount)?;
+                self.run_operation(caller, operation)
+            }
+            CompoundAction::RefundTokens { market, amount } => {
+                self.pool.accrue_interest(now);
+                let operation = self.pool.refund(caller, market, amount)?;
+                self.run_operation(caller, operation)
+            }
+            CompoundAction::WithdrawTokens { market, amount } => {
+                self.pool.accrue_interest(now);
+                let operation = self.pool.withdraw(caller, market, amount)?;
+                self.run_operation(caller, operation)
+            }
+            CompoundAction::Liquidate {
+                borrower,
+                repay_market,
+                collateral_market,
+                repay_amount,
+            } => {
+                self.pool.accrue_interest(now);
+                let operation = self.pool.liquidate(
+                    caller,
+                    borrower,
+                    repay_market,
+                    collateral_market,
+                    repay_amount,
+                )?;
+                self.run_operation(caller, operation)
+            }
+            CompoundAction::EnterMarket { market } => {
+                self.pool.enter_market(caller, market).map(Step::Done)
+            }
+            CompoundAction::ExitMarket { market } => {
+                self.pool.accrue_interest(now);
+                self.pool.exit_market(caller, market).map(Step::Done)
+            }
+            CompoundAction::QueueProposal { change } => {
+                self.queue_proposal(caller, change, now).map(Step::Done)
+            }
+            CompoundAction::CancelProposal { proposal_id } => self
+                .cancel_proposal(caller, proposal_id, now)
+                .map(Step::Done),
+            CompoundAction::ExecuteProposal { proposal_id } => {
+                match self.execute_proposal(caller, proposal_id, now)? {
+                    // рынок открыт, но пока нет его ctoken, событие отдается после его создания
+                    CompoundEvent::MarketAdded { market } => Ok(Step::CreateCToken {
+                        code: self.ctoken_code,
+                        market,
+                    }),
+                    event => Ok(Step::Done(event)),
+                }
+            }
+            CompoundAction::TransferAdmin { new_admin } => {
+                self.transfer_admin(caller, new_admin).map(Step::Done)
+            }
+            CompoundAction::AcceptAdmin => self.accept_admin(caller).map(Step::Done),
+            CompoundAction::AddReserves { market, amount } => {
+                self.add_reserves(caller, market, amount, now)
+            }
+            CompoundAction::ReduceReserves { market, amount } => {
+                self.reduce_reserves(caller, market, amount, now)
+            }
+            CompoundAction::SetPaused {
+                market,
+                action,
+                paused,
+            } => self
+                .set_paused(caller, market, action, paused)
+                .map(Step::Done),
+            CompoundAction::ResolveTransaction { tx_id, transferred } => self
+                .resolve_transaction(caller, tx_id, transferred, now)
+                .map(Step::Done),
+            CompoundAction::CToken { sender, action } => {
+                let market = self.ctoken_market(caller)?;
+                self.ctoken(sender, market, action, now).map(Step::Done)
+            }
+        }
+    }
+
+    // у каждой операции один перевод токенов, поэтому возвращать при неудаче нечего:
+    // таблицы меняются только после того, как перевод прошел
+    fn run_operation(
+        &mut self,
+        user: ActorId,
+        operation: Operation,
+    ) -> Result<Step, CompoundError> {
+        let transfer = self.transfer(user, &operation)?;
+        // заемщика ликвидация блокирует сама, когда уже проверено, что его можно ликвидировать
+        let locked = match operation {
+            Operation::Liquidate { borrower, .. } => vec![borrower],
+            _ => vec![],
+        };
+        self.lock_accounts(&locked)?;
+        self.pool.reserve(&operation)?;
+        Ok(self.start_transaction(user, operation, transfer, locked))
+    }
+
+    // перевод токенов, после которого операцию можно применить к таблицам; ctokens ведет сам
+    // контракт, поэтому они выпускаются, сжигаются и передаются уже в `apply_operation`
+    fn transfer(&self, user: ActorId, operation: &Operation) -> Result<Transfer, CompoundError> {
+        let program_id = self.program_id;
+        let transfer = match *operation {
+            Operation::Lend { market, amount, .. } => Transfer {
+                // переводим amount токенов с типом token_address с user на адрес контракта (program_id)
+                token: self.pool.market(&market)?.token_address,
+                from: user,
+                to: program_id,
+                amount: amount.0,
+            },
+            Operation::Withdraw { market, amount, .. } => Transfer {
+                // взамен ctokens трансферим tokens
+                token: self.pool.market(&market)?.token_address,
+                from: program_id,
+                to: user,
+                amount: amount.0,
+            },
+            Operation::Borrow { market, amount } => Transfer {
+                // переводим пользователю занятые токены
+                token: self.pool.market(&market)?.token_address,
+                from: program_id,
+                to: user,
+                amount: amount.0,
+            },
+            Operation::Refund { market, amount } => Transfer {
+                // переводим токены пользователя на адрес контракта
+                token: self.pool.market(&market)?.token_address,
+                from: user,
+                to: program_id,
+                amount: amount.0,
+            },
+            Operation::Liquidate {
+                repay_market,
+                repay_amount,
+                ..
+            } => Transfer {
+                // ликвидатор гасит долг своими токенами, ctokens заемщика переходят к нему в таблицах
+                token: self.pool.market(&repay_market)?.token_address,
+                from: user,
+                to: program_id,
+                amount: repay_amount.0,
+            },
+        };
+        Ok(transfer)
+    }
+
+    // ответ на запрос к состоянию; проценты начисляются на текущее время, чтобы ответ совпал
+    // с тем, что увидит следующее действие
+    pub fn query(&mut self, query: StateQuery, now: u64) -> StateReply {
+        self.pool.accrue_interest(now);
+
+        match query {
+            StateQuery::State => StateReply::State(Box::new(CompoundState::from(&*self))),
+            StateQuery::Markets => {
+                StateReply::Markets(self.pool.markets.values().map(Into::into).collect())
+            }
+            StateQuery::Market { market } => StateReply::Market(
+                self.pool
+                    .markets
+                    .get(&market)
+                    .map(|market| Box::new(market.into())),
+            ),
+            StateQuery::UserAssets { user } => StateReply::UserAssets(
+                self.pool
+                    .markets
+                    .iter()
+                    .filter_map(|(id, market)| {
+                        market
+                            .user_assets
+                            .get(&user)
+                            .map(|assets| (*id, assets.clone()))
+                    })
+                    .collect(),
+            ),
+            StateQuery::AccountLiquidity { user, market } => {
+                StateReply::AccountLiquidity(self.pool.account_liquidity(&user, &market).ok())
+            }
+        }
+    }
+}
+
+impl From<&Compound> for CompoundState {
+    fn from(compound: &Compound) -> Self {
+        Self {
+            admin: compound.admin,
+            pending_admin: compound.pending_admin,
+            pause_guardian: compound.pause_guardian,
+            close_factor: compound.pool.close_factor,
+            liquidation_incentive: compound.pool.liquidation_incentive,
+            oracle: compound.oracle.address,
+            max_price_age: compound.oracle.max_price_age,
+            timelock_delay: compound.timelock.delay,
+            proposals: compound
+                .timelock
+                .proposals
+                .iter()
+                .map(|(id, proposal)| (*id, proposal.clone()))
+                .collect(),
+            init_time: compound.init_time,
+            markets: compound
+                .pool
+                .markets
+                .iter()
+                .map(|(id, market)| (*id, market.into()))
+                .collect(),
+            ctoken_code: compound.ctoken_code,
+            transactions: compound
+                .transactions
+                .iter()
+                .map(|(id, tx)| (*id, tx.clone()))
+                .collect(),
+            locked_accounts: compound.locked_accounts.iter().copied().collect(),
+        }
+    }
+}
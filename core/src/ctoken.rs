@@ -0,0 +1,132 @@
+// ctokens рынков: контракт сам ведет их балансы в `user_assets`, поэтому ctokens не нужно заранее
+// переводить контракту. Для каждого рынка создается контракт ctoken, который принимает стандартные
+// действия fungible-token и пересылает их сюда от имени отправителя. Перевод уменьшает вклад
+// отправителя и проверяется так же, как вывод: долги должны остаться обеспечены
+
+use crate::{asserts, Compound, Step};
+use compound_io::{
+    ft::{FTAction, FTEvent},
+    *,
+};
+use gstd::ActorId;
+
+impl Compound {
+    // адрес созданного контракта ctoken рынка
+    pub fn ctoken_created(
+        &mut self,
+        market_id: ActorId,
+        ctoken: ActorId,
+    ) -> Result<Step, CompoundError> {
+        self.pool.market_mut(&market_id)?.ctoken = ctoken;
+        Ok(Step::Done(CompoundEvent::MarketAdded { market: market_id }))
+    }
+
+    // рынок, чей контракт ctoken прислал действие; другим отправителям действие недоступно
+    pub fn ctoken_market(&self, caller: ActorId) -> Result<ActorId, CompoundError> {
+        self.pool
+            .markets
+            .values()
+            .find(|market| !market.ctoken.is_zero() && market.ctoken == caller)
+            .map(|market| market.token_address)
+            .ok_or(CompoundError::Unauthorized)
+    }
+
+    pub fn ctoken(
+        &mut self,
+        sender: ActorId,
+        market_id: ActorId,
+        action: FTAction,
+        now: u64,
+    ) -> Result<CompoundEvent, CompoundError> {
+        let event = match action {
+            FTAction::Transfer { from, to, amount } => {
+                self.pool.accrue_interest(now);
+                self.transfer_ctokens(sender, market_id, from, to, CTokens(amount))?;
+                FTEvent::Transfer { from, to, amount }
+            }
+            FTAction::Approve { to, amount } => {
+                asserts::not_zero_address(&to)?;
+                self.pool
+                    .market_mut(&market_id)?
+                    .allowances
+                    .insert((sender, to), CTokens(amount));
+                FTEvent::Approve {
+                    from: sender,
+                    to,
+                    amount,
+                }
+            }
+            FTAction::TotalSupply => {
+                FTEvent::TotalSupply(self.pool.market(&market_id)?.total_ctokens.0)
+            }
+            FTAction::BalanceOf(account) => FTEvent::Balance(
+                self.pool
+                    .market(&market_id)?
+                    .user_assets
+                    .get(&account)
+                    .map(|assets| assets.get_lent_amount().0)
+                    .unwrap_or_default(),
+            ),
+            // ctokens выпускаются только за вклад и сжигаются только при выводе
+            FTAction::Mint(_) | FTAction::Burn(_) => return Err(CompoundError::Unauthorized),
+        };
+
+        Ok(CompoundEvent::CToken {
+            market: market_id,
+            event,
+        })
+    }
+
+    // `caller` переводит свои ctokens или чужие в пределах разрешения владельца
+    fn transfer_ctokens(
+        &mut self,
+        caller: ActorId,
+        market_id: ActorId,
+        from: ActorId,
+        to: ActorId,
+        amount: CTokens,
+    ) -> Result<(), CompoundError> {
+        asserts::greater_zero(amount.0)?;
+        asserts::not_zero_address(&to)?;
+        let market = self.pool.market(&market_id)?;
+
+        let assets = market
+            .user_assets
+            .get(&from)
+            .ok_or(CompoundError::NoAssets(from))?;
+        if amount > assets.get_lent_amount() {
+            return Err(CompoundError::AmountTooBig);
+        }
+        let allowance = market
+            .allowances
+            .get(&(from, caller))
+            .copied()
+            .unwrap_or_default();
+        if caller != from && allowance < amount {
+            return Err(CompoundError::NotEnoughAllowance);
+        }
+        // вклад вне залога или без долгов переводится целиком, иначе долги должны остаться обеспечены
+        let liquidity = self.pool.collateral_liquidity(&from, &market_id)?;
+        if assets.is_collateral
+            && liquidity.borrow_value != 0
+            && liquidity.max_withdraw < amount.to_tokens(market.exchange_rate(), Rounding::Up)
+        {
+            return Err(CompoundError::InsufficientCollateral);
+        }
+
+        let market = self.pool.market_mut(&market_id)?;
+        if caller != from {
+            market.allowances.insert((from, caller), allowance - amount);
+        }
+        market
+            .user_assets
+            .entry(from)
+            .and_modify(|assets| assets.sub_lend(amount));
+        market
+            .user_assets
+            .entry(to)
+            .and_modify(|assets| assets.add_lend(amount))
+            .or_insert_with(|| Assets::new(amount));
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+#![no_std]
+
+// протокол без переводов токенов и сообщений: `Pool` ведет учет рынков - проверки действий,
+// начисление процентов, залог и ликвидации, а `Compound` добавляет к нему управление, паузы,
+// резервы и блокировки счетов и отдает переводы и запросы цен адаптеру. Контракт выполняет эти
+// эффекты через gstd, а тесты и симулятор прогоняют те же расчеты на хосте
+
+mod admin;
+pub mod asserts;
+mod compound;
+mod ctoken;
+mod locks;
+mod market;
+pub mod oracle;
+mod pause;
+mod pool;
+mod reserves;
+pub mod timelock;
+mod transactions;
+
+pub use compound::{Compound, Pending, Step};
+pub use market::Market;
+pub use pool::Pool;
@@ -0,0 +1,48 @@
+// блокировки счетов: пока действие пользователя ждет переводов токенов, другие действия
+// с тем же счетом отклоняются, иначе они могли бы пройти проверки по устаревшему состоянию
+
+use crate::Compound;
+use compound_io::{ft::FTAction, CompoundAction, CompoundError};
+use gstd::{prelude::*, ActorId};
+
+impl Compound {
+    // счета, которые меняет действие; блокируются до любых проверок, поэтому здесь только счета,
+    // которые автор действия вправе занять: заемщика ликвидация блокирует сама после проверки
+    // залога, а действия ctokens от чужих адресов не блокируют ничего
+    pub fn action_accounts(&self, action: &CompoundAction, caller: ActorId) -> Vec<ActorId> {
+        match action {
+            CompoundAction::CToken { .. } if self.ctoken_market(caller).is_err() => vec![],
+            // перевод ctokens меняет вклады отправителя и получателя, разрешение - только счет владельца
+            CompoundAction::CToken { sender, action } => match action {
+                FTAction::Transfer { from, to, .. } => vec![*from, *to],
+                FTAction::Approve { .. } => vec![*sender],
+                _ => vec![],
+            },
+            CompoundAction::QueueProposal { .. }
+            | CompoundAction::CancelProposal { .. }
+            | CompoundAction::ExecuteProposal { .. }
+            | CompoundAction::TransferAdmin { .. }
+            | CompoundAction::AcceptAdmin
+            | CompoundAction::SetPaused { .. }
+            | CompoundAction::ResolveTransaction { .. } => vec![],
+            _ => vec![caller],
+        }
+    }
+
+    pub fn lock_accounts(&mut self, accounts: &[ActorId]) -> Result<(), CompoundError> {
+        if let Some(account) = accounts
+            .iter()
+            .find(|account| self.locked_accounts.contains(account))
+        {
+            return Err(CompoundError::AccountLocked(*account));
+        }
+        self.locked_accounts.extend(accounts.iter().copied());
+        Ok(())
+    }
+
+    pub fn unlock_accounts(&mut self, accounts: &[ActorId]) {
+        for account in accounts {
+            self.locked_accounts.remove(account);
+        }
+    }
+}
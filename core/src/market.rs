@@ -0,0 +1,239 @@
+// один рынок: токен со своей моделью ставки, залоговым коэффициентом и пулом; ctokens рынка
+// учитываются здесь же, в `user_assets` и `allowances`
+
+use crate::asserts;
+use compound_io::*;
+use gstd::{
+    collections::{BTreeMap, BTreeSet},
+    prelude::*,
+    ActorId,
+};
+
+#[derive(Debug, Default, Clone)]
+pub struct Market {
+    pub token_address: ActorId,                   // id контракта токена
+    pub ctoken: ActorId,                          // контракт ctoken рынка, нулевой - еще не создан
+    pub collateral_factor: Wad,                   // какую долю вклада можно занять под залог
+    pub rate_model: RateModel, // модель ставки по кредиту в зависимости от загрузки пула
+    pub reserve_factor: Wad,   // доля процентов заемщиков, которая уходит в резервы протокола
+    pub supply_cap: Tokens,    // предел вкладов вместе с процентами, 0 - без предела
+    pub borrow_cap: Tokens,    // предел кредитов вместе с процентами, 0 - без предела
+    pub paused_actions: BTreeSet<PausableAction>, // приостановленные действия
+    pub ctoken_rate: Wad,      // начальный курс: сколько ctokens дается за один токен
+    pub fallback_price: Wad,   // цена на случай, если оракул недоступен
+    pub price: Wad,            // последняя полученная цена токена в общей единице
+    pub price_time: u64,       // когда цена была обновлена
+    pub user_assets: BTreeMap<ActorId, Assets>, // таблица вкладов и кредитов с процентами для пользователей
+    pub allowances: BTreeMap<(ActorId, ActorId), CTokens>, // сколько ctokens владелец разрешил перевести другому
+    pub borrow_index: Wad,                                 // индекс наращивания кредитов
+    pub accrual_time: u64,                                 // время последнего начисления процентов
+    pub total_cash: Tokens,                                // сколько токенов лежит на контракте
+    pub outgoing_cash: Tokens, // часть `total_cash`, которую уже переводят из пула и ждут ответа
+    pub incoming_supply: Tokens, // вклады, чей перевод в пул еще не подтвержден
+    pub pending_borrows: Tokens, // кредиты, чей перевод еще не подтвержден
+    pub total_borrows: Tokens, // сколько токенов занято вместе с процентами
+    pub total_reserves: Tokens, // резервы протокола
+    pub total_ctokens: CTokens, // сколько ctokens выдано пользователям
+}
+
+impl Market {
+    pub fn new(config: MarketConfig, now: u64) -> Result<Self, CompoundError> {
+        asserts::not_zero_address(&config.token_address)?; // проверяем, что переданные данные корректны
+        asserts::collateral_factor(config.collateral_factor)?;
+        asserts::rate_model(&config.rate_model)?;
+        asserts::reserve_factor(config.reserve_factor)?;
+        asserts::greater_zero(config.ctoken_rate.0)?;
+
+        Ok(Self {
+            token_address: config.token_address,
+            collateral_factor: config.collateral_factor,
+            rate_model: config.rate_model,
+            reserve_factor: config.reserve_factor,
+            supply_cap: config.supply_cap,
+            borrow_cap: config.borrow_cap,
+            ctoken_rate: config.ctoken_rate,
+            fallback_price: config.fallback_price,
+            price: config.fallback_price,
+            price_time: now,
+            borrow_index: Wad::ONE,
+            accrual_time: now,
+            ..Default::default()
+        })
+    }
+
+    pub fn accrue_interest(&mut self, now: u64) {
+        // наращиваем индекс и сумму кредитов за время, прошедшее с прошлого начисления,
+        // доля `reserve_factor` процентов заемщиков уходит в резервы, остальное увеличивает
+        // курс ctoken и достается вкладчикам
+        let elapsed = now.saturating_sub(self.accrual_time);
+        if elapsed == 0 {
+            return;
+        }
+
+        let borrow_index = accrue_index(self.borrow_index, self.borrow_rate(), elapsed);
+        let total_borrows = Tokens(mul_div(
+            self.total_borrows.0,
+            borrow_index.0,
+            self.borrow_index.0,
+            Rounding::Up,
+        ));
+        let interest = total_borrows - self.total_borrows;
+        self.total_reserves += Tokens(self.reserve_factor.scale(interest.0, Rounding::Down));
+        self.total_borrows = total_borrows;
+        self.borrow_index = borrow_index;
+        self.accrual_time = now;
+    }
+
+    // позиция пользователя в этом рынке для проверки залога
+    pub fn position(&self, user: &ActorId) -> MarketPosition {
+        MarketPosition::new(
+            self.user_assets.get(user),
+            self.exchange_rate(),
+            self.borrow_index,
+            self.price,
+            self.collateral_factor,
+        )
+    }
+
+    pub fn borrow_rate(&self) -> Wad {
+        self.rate_model.borrow_rate(
+            self.total_cash.0,
+            self.total_borrows.0,
+            self.total_reserves.0,
+        )
+    }
+
+    // вкладчикам достаются проценты за вычетом доли резервов
+    pub fn supply_rate(&self) -> Wad {
+        self.rate_model
+            .supply_rate(
+                self.total_cash.0,
+                self.total_borrows.0,
+                self.total_reserves.0,
+            )
+            .mul(Wad::ONE - self.reserve_factor, Rounding::Down)
+    }
+
+    // токены, которые еще можно вывести или занять: без тех, что уже переводятся из пула
+    pub fn free_cash(&self) -> Tokens {
+        free_cash(self.total_cash, self.outgoing_cash)
+    }
+
+    // все вклады рынка вместе с процентами: свободные и занятые токены за вычетом резервов
+    pub fn total_supply(&self) -> Tokens {
+        (self.total_cash + self.total_borrows).saturating_sub(self.total_reserves)
+    }
+
+    // вклад `amount` не выведет вклады рынка за предел вместе с вкладами, которые еще переводятся
+    pub fn check_supply_cap(&self, amount: Tokens) -> Result<(), CompoundError> {
+        if !self.supply_cap.is_zero()
+            && self.total_supply() + self.incoming_supply + amount > self.supply_cap
+        {
+            return Err(CompoundError::SupplyCapExceeded(self.token_address));
+        }
+        Ok(())
+    }
+
+    // кредит `amount` не выведет кредиты рынка за предел вместе с кредитами, которые еще переводятся
+    pub fn check_borrow_cap(&self, amount: Tokens) -> Result<(), CompoundError> {
+        if self.borrow_room().is_some_and(|room| amount > room) {
+            return Err(CompoundError::BorrowCapExceeded(self.token_address));
+        }
+        Ok(())
+    }
+
+    // сколько еще можно занять до предела кредитов, `None` - предела нет
+    pub fn borrow_room(&self) -> Option<Tokens> {
+        borrow_room(self.borrow_cap, self.total_borrows, self.pending_borrows)
+    }
+
+    pub fn exchange_rate(&self) -> Wad {
+        exchange_rate(
+            self.total_cash,
+            self.total_borrows,
+            self.total_reserves,
+            self.total_ctokens,
+            self.ctoken_rate,
+        )
+    }
+
+    // сколько ctokens стоят токены по текущему курсу: при вкладе округляем вниз,
+    // при выводе - вверх, чтобы остаток всегда доставался пулу
+    pub fn count_ctokens(&self, tokens_amount: Tokens, rounding: Rounding) -> CTokens {
+        tokens_amount.to_ctokens(self.exchange_rate(), rounding)
+    }
+
+    // сколько токенов стоят ctokens по текущему курсу, с округлением вниз
+    pub fn count_tokens(&self, ctokens_amount: CTokens) -> Tokens {
+        ctokens_amount.to_tokens(self.exchange_rate(), Rounding::Down)
+    }
+}
+
+impl From<&Market> for MarketSummary {
+    fn from(market: &Market) -> Self {
+        Self {
+            market: market.token_address,
+            ctoken: market.ctoken,
+            total_supply: market.total_supply(),
+            total_cash: market.total_cash,
+            total_borrows: market.total_borrows,
+            total_reserves: market.total_reserves,
+            total_ctokens: market.total_ctokens,
+            utilization: rate_model::utilization(
+                market.total_cash.0,
+                market.total_borrows.0,
+                market.total_reserves.0,
+            ),
+            borrow_rate: market.borrow_rate(),
+            supply_rate: market.supply_rate(),
+            exchange_rate: market.exchange_rate(),
+            price: market.price,
+            collateral_factor: market.collateral_factor,
+            reserve_factor: market.reserve_factor,
+            supply_cap: market.supply_cap,
+            borrow_cap: market.borrow_cap,
+            paused_actions: market.paused_actions.iter().copied().collect(),
+        }
+    }
+}
+
+impl From<&Market> for MarketState {
+    fn from(market: &Market) -> Self {
+        Self {
+            token_address: market.token_address,
+            ctoken: market.ctoken,
+            collateral_factor: market.collateral_factor,
+            rate_model: market.rate_model.clone(),
+            reserve_factor: market.reserve_factor,
+            supply_cap: market.supply_cap,
+            borrow_cap: market.borrow_cap,
+            paused_actions: market.paused_actions.iter().copied().collect(),
+            borrow_rate: market.borrow_rate(),
+            supply_rate: market.supply_rate(),
+            ctoken_rate: market.ctoken_rate,
+            fallback_price: market.fallback_price,
+            price: market.price,
+            price_time: market.price_time,
+            user_assets: market
+                .user_assets
+                .iter()
+                .map(|(id, assets)| (*id, assets.clone()))
+                .collect(),
+            allowances: market
+                .allowances
+                .iter()
+                .map(|((owner, spender), amount)| (*owner, *spender, *amount))
+                .collect(),
+            borrow_index: market.borrow_index,
+            accrual_time: market.accrual_time,
+            total_cash: market.total_cash,
+            outgoing_cash: market.outgoing_cash,
+            incoming_supply: market.incoming_supply,
+            pending_borrows: market.pending_borrows,
+            total_borrows: market.total_borrows,
+            total_reserves: market.total_reserves,
+            total_ctokens: market.total_ctokens,
+            exchange_rate: market.exchange_rate(),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// цены токенов рынков для сравнения залога и долгов в одной общей единице
+
+use compound_io::{CompoundError, Wad};
+use gstd::ActorId;
+
+#[derive(Debug, Default, Clone)]
+pub struct PriceOracle {
+    pub address: ActorId,   // id контракта оракула, нулевой - оракула нет
+    pub max_price_age: u64, // через сколько секунд цена оракула считается устаревшей
+}
+
+impl PriceOracle {
+    // свежая цена из ответа оракула, а если оракул не ответил или цена устарела - `fallback_price`
+    pub fn price(
+        &self,
+        token: ActorId,
+        fetched: Option<(Wad, u64)>,
+        fallback_price: Wad,
+        now: u64,
+    ) -> Result<Wad, CompoundError> {
+        let price = match fetched {
+            Some((price, updated_at))
+                if !price.is_zero() && now.saturating_sub(updated_at) <= self.max_price_age =>
+            {
+                price
+            }
+            _ => fallback_price,
+        };
+        if price.is_zero() {
+            return Err(CompoundError::PriceUnavailable(token));
+        }
+        Ok(price)
+    }
+}
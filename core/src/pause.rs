@@ -0,0 +1,35 @@
+// экстренная пауза: хранитель паузы может приостановить вклады, кредиты, выводы и ликвидации
+// в отдельном рынке, а возобновить их может только администратор; погашение кредитов не
+// приостанавливается, чтобы заемщики всегда могли закрыть долг
+
+use crate::Compound;
+use compound_io::*;
+use gstd::ActorId;
+
+impl Compound {
+    pub fn set_paused(
+        &mut self,
+        caller: ActorId,
+        market_id: ActorId,
+        action: PausableAction,
+        paused: bool,
+    ) -> Result<CompoundEvent, CompoundError> {
+        let is_guardian = !self.pause_guardian.is_zero() && caller == self.pause_guardian;
+        if caller != self.admin && !(paused && is_guardian) {
+            return Err(CompoundError::Unauthorized);
+        }
+
+        let market = self.pool.market_mut(&market_id)?;
+        if paused {
+            market.paused_actions.insert(action);
+        } else {
+            market.paused_actions.remove(&action);
+        }
+
+        Ok(CompoundEvent::PausedSet {
+            market: market_id,
+            action,
+            paused,
+        })
+    }
+}
@@ -0,0 +1,507 @@
+// все рынки вместе: действие сначала проверяется и превращается в `Operation`, а таблицы
+// меняются в `apply_operation`, когда перевод токенов уже прошел. Токены, которые уходят из пула,
+// и суммы, которые войдут в пределы вкладов и кредитов, откладываются еще до перевода в `reserve`,
+// чтобы их не обещали другим счетам
+
+use crate::{asserts, Market};
+use compound_io::*;
+use gstd::{collections::BTreeMap, prelude::*, ActorId};
+
+#[derive(Debug, Default, Clone)]
+pub struct Pool {
+    pub close_factor: Wad, // какую часть долга можно погасить за одну ликвидацию
+    pub liquidation_incentive: Wad, // бонус ликвидатора как доля от погашенного долга
+    pub markets: BTreeMap<ActorId, Market>, // рынки по адресу токена
+}
+
+impl Pool {
+    pub fn new(close_factor: Wad, liquidation_incentive: Wad) -> Result<Self, CompoundError> {
+        asserts::close_factor(close_factor)?;
+        asserts::liquidation_incentive(liquidation_incentive)?;
+
+        Ok(Self {
+            close_factor,
+            liquidation_incentive,
+            ..Default::default()
+        })
+    }
+
+    pub fn list_market(&mut self, config: MarketConfig, now: u64) -> Result<(), CompoundError> {
+        self.check_new_market(&config)?;
+        let market = Market::new(config, now)?;
+        self.markets.insert(market.token_address, market);
+        Ok(())
+    }
+
+    // рынок еще не открыт и его параметры в пределах
+    pub fn check_new_market(&self, config: &MarketConfig) -> Result<(), CompoundError> {
+        if self.markets.contains_key(&config.token_address) {
+            return Err(CompoundError::MarketAlreadyListed(config.token_address));
+        }
+        Market::new(config.clone(), 0).map(|_| ())
+    }
+
+    pub fn market(&self, market_id: &ActorId) -> Result<&Market, CompoundError> {
+        self.markets
+            .get(market_id)
+            .ok_or(CompoundError::MarketNotListed(*market_id))
+    }
+
+    pub fn market_mut(&mut self, market_id: &ActorId) -> Result<&mut Market, CompoundError> {
+        self.markets
+            .get_mut(market_id)
+            .ok_or(CompoundError::MarketNotListed(*market_id))
+    }
+
+    // начисляем проценты во всех рынках, так как залог пользователя учитывается по всем сразу
+    pub fn accrue_interest(&mut self, now: u64) {
+        self.markets
+            .values_mut()
+            .for_each(|market| market.accrue_interest(now));
+    }
+
+    pub fn set_price(
+        &mut self,
+        market_id: &ActorId,
+        price: Wad,
+        now: u64,
+    ) -> Result<(), CompoundError> {
+        let market = self.market_mut(market_id)?;
+        market.price = price;
+        market.price_time = now;
+        Ok(())
+    }
+
+    pub fn check_not_paused(
+        &self,
+        market_id: &ActorId,
+        action: PausableAction,
+    ) -> Result<(), CompoundError> {
+        if self.market(market_id)?.paused_actions.contains(&action) {
+            return Err(CompoundError::ActionPaused {
+                market: *market_id,
+                action,
+            });
+        }
+        Ok(())
+    }
+
+    // запас по залогу пользователя во всех рынках, одинаковый для действий и запросов к состоянию;
+    // вывод и заем ограничены еще свободными токенами рынка и пределом кредитов
+    pub fn account_liquidity(
+        &self,
+        user: &ActorId,
+        market_id: &ActorId,
+    ) -> Result<AccountLiquidity, CompoundError> {
+        let market = self.market(market_id)?;
+        Ok(self
+            .collateral_liquidity(user, market_id)?
+            .limit_by_market(market.free_cash(), market.borrow_room()))
+    }
+
+    // запас только по залогу, без ограничений рынка: для переводов ctokens и исключения из залога,
+    // которые токены из пула не забирают
+    pub fn collateral_liquidity(
+        &self,
+        user: &ActorId,
+        market_id: &ActorId,
+    ) -> Result<AccountLiquidity, CompoundError> {
+        let positions: Vec<_> = self
+            .markets
+            .values()
+            .map(|market| market.position(user))
+            .collect();
+        Ok(AccountLiquidity::new(
+            &positions,
+            &self.market(market_id)?.position(user),
+        ))
+    }
+
+    pub fn lend(&self, market_id: ActorId, amount: Tokens) -> Result<Operation, CompoundError> {
+        asserts::greater_zero(amount.0)?; // проверяем, что сумма положительна
+        self.check_not_paused(&market_id, PausableAction::Lend)?;
+        let market = self.market(&market_id)?;
+        market.check_supply_cap(amount)?;
+
+        Ok(Operation::Lend {
+            market: market_id,
+            amount,
+        })
+    }
+
+    pub fn borrow(
+        &self,
+        user: ActorId,
+        market_id: ActorId,
+        amount: Tokens,
+    ) -> Result<Operation, CompoundError> {
+        asserts::greater_zero(amount.0)?; // проверяем на положительность
+        self.check_not_paused(&market_id, PausableAction::Borrow)?;
+        let market = self.market(&market_id)?;
+
+        // проверяем, что пользователь вложил деньги хотя бы в один рынок (нужно для исбыточного обеспечения)
+        if !self
+            .markets
+            .values()
+            .any(|market| market.user_assets.contains_key(&user))
+        {
+            return Err(CompoundError::NoAssets(user));
+        }
+        // проверяем, что в рынке хватает свободных токенов
+        if market.free_cash() < amount {
+            return Err(CompoundError::NotEnoughCash);
+        }
+        market.check_borrow_cap(amount)?;
+        // проверяем, что пользователь может занять запрошенное количество денег под залог всех рынков
+        if self
+            .account_liquidity(&user, &market_id)?
+            .available_to_borrow
+            < amount
+        {
+            return Err(CompoundError::InsufficientCollateral);
+        }
+
+        Ok(Operation::Borrow {
+            market: market_id,
+            amount,
+        })
+    }
+
+    pub fn refund(
+        &self,
+        user: ActorId,
+        market_id: ActorId,
+        amount: Tokens,
+    ) -> Result<Operation, CompoundError> {
+        asserts::greater_zero(amount.0)?; // проверяем на положительность
+        let market = self.market(&market_id)?;
+
+        let assets = market // проверяем, что у пользователя есть счет и на нем достаточно токенов
+            .user_assets
+            .get(&user)
+            .ok_or(CompoundError::NoAssets(user))?;
+        if assets.get_borrow_amount(market.borrow_index) < amount {
+            return Err(CompoundError::AmountTooBig);
+        }
+
+        Ok(Operation::Refund {
+            market: market_id,
+            amount,
+        })
+    }
+
+    pub fn withdraw(
+        &self,
+        user: ActorId,
+        market_id: ActorId,
+        amount: Tokens,
+    ) -> Result<Operation, CompoundError> {
+        asserts::greater_zero(amount.0)?; // проверяем на положительность
+        self.check_not_paused(&market_id, PausableAction::Withdraw)?;
+        let market = self.market(&market_id)?;
+
+        let assets = market // проверяем, что у пользователя есть баланс
+            .user_assets
+            .get(&user)
+            .ok_or(CompoundError::NoAssets(user))?;
+        // проверяем, что на счете достточное количество токенов: вклад хранится в ctokens,
+        // а выводится в токенах, поэтому сравниваем по текущему курсу
+        if amount > market.count_tokens(assets.get_lent_amount()) {
+            return Err(CompoundError::AmountTooBig);
+        }
+        // проверяем, что в рынке хватает свободных токенов
+        if market.free_cash() < amount {
+            return Err(CompoundError::NotEnoughCash);
+        }
+        // проверяем, что после вывода токенов не сломается концепция исбыточного обеспечения
+        if self.account_liquidity(&user, &market_id)?.max_withdraw < amount {
+            return Err(CompoundError::InsufficientCollateral);
+        }
+
+        Ok(Operation::Withdraw {
+            market: market_id,
+            amount,
+            ctokens_amount: market.count_ctokens(amount, Rounding::Up),
+        })
+    }
+
+    // любой может погасить часть долга заемщика, у которого долг превысил допустимый залогом,
+    // и получить его ctokens со скидкой
+    pub fn liquidate(
+        &self,
+        liquidator: ActorId,
+        borrower: ActorId,
+        repay_market_id: ActorId,
+        collateral_market_id: ActorId,
+        repay_amount: Tokens,
+    ) -> Result<Operation, CompoundError> {
+        asserts::greater_zero(repay_amount.0)?; // проверяем на положительность
+        if liquidator == borrower {
+            return Err(CompoundError::SelfLiquidation);
+        }
+        self.check_not_paused(&repay_market_id, PausableAction::Liquidate)?;
+        self.check_not_paused(&collateral_market_id, PausableAction::Liquidate)?;
+        let repay_market = self.market(&repay_market_id)?;
+        let collateral_market = self.market(&collateral_market_id)?;
+
+        // ликвидировать можно только заемщика без достаточного залога
+        if !self
+            .account_liquidity(&borrower, &collateral_market_id)?
+            .is_undercollateralized()
+        {
+            return Err(CompoundError::NotUndercollateralized(borrower));
+        }
+        // за раз гасится не больше доли `close_factor` долга в рынке
+        if repay_amount.0
+            > self.close_factor.scale(
+                repay_market.position(&borrower).borrow_amount.0,
+                Rounding::Down,
+            )
+        {
+            return Err(CompoundError::CloseFactorExceeded);
+        }
+
+        let collateral = collateral_market
+            .user_assets
+            .get(&borrower)
+            .filter(|assets| assets.is_collateral)
+            .ok_or(CompoundError::NotEnoughCollateral)?;
+        // погашенный долг переводится в токены залога по ценам оракула и увеличивается на бонус
+        let repay_value = repay_market.price.scale(repay_amount.0, Rounding::Down);
+        let seize_value =
+            (Wad::ONE + self.liquidation_incentive).scale(repay_value, Rounding::Down);
+        let seize_amount = collateral_market.price.unscale(seize_value, Rounding::Down);
+        let ctokens_seized = collateral_market.count_ctokens(Tokens(seize_amount), Rounding::Down);
+        // у заемщика должно хватать залога на погашенный долг с бонусом
+        if ctokens_seized > collateral.get_lent_amount() {
+            return Err(CompoundError::NotEnoughCollateral);
+        }
+
+        Ok(Operation::Liquidate {
+            borrower,
+            repay_market: repay_market_id,
+            collateral_market: collateral_market_id,
+            repay_amount,
+            ctokens_seized,
+        })
+    }
+
+    // включаем вклад в рынке в залог
+    pub fn enter_market(
+        &mut self,
+        user: ActorId,
+        market_id: ActorId,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.market_mut(&market_id)?
+            .user_assets
+            .entry(user)
+            .or_default()
+            .is_collateral = true;
+
+        Ok(CompoundEvent::MarketEntered {
+            market: market_id,
+            address: user,
+        })
+    }
+
+    // исключаем вклад из залога, если без него оставшиеся долги все еще обеспечены
+    pub fn exit_market(
+        &mut self,
+        user: ActorId,
+        market_id: ActorId,
+    ) -> Result<CompoundEvent, CompoundError> {
+        let market = self.market(&market_id)?;
+        if !market.user_assets.contains_key(&user) {
+            return Err(CompoundError::NoAssets(user));
+        }
+        if self.collateral_liquidity(&user, &market_id)?.max_withdraw
+            < market.position(&user).collateral_amount
+        {
+            return Err(CompoundError::InsufficientCollateral);
+        }
+
+        self.market_mut(&market_id)?
+            .user_assets
+            .entry(user)
+            .and_modify(|assets| assets.is_collateral = false);
+
+        Ok(CompoundEvent::MarketExited {
+            market: market_id,
+            address: user,
+        })
+    }
+
+    // откладываем до перевода токены, которые операция выводит из пула, и ее долю пределов
+    // вкладов и кредитов, чтобы пока ждем ответа, их не вывел и не занял другой счет;
+    // `total_cash` и курс ctoken меняются только после перевода
+    pub fn reserve(&mut self, operation: &Operation) -> Result<(), CompoundError> {
+        match *operation {
+            Operation::Lend { market, amount } => {
+                self.market_mut(&market)?.incoming_supply += amount;
+            }
+            Operation::Withdraw { market, amount, .. } => {
+                self.market_mut(&market)?.outgoing_cash += amount;
+            }
+            Operation::Borrow { market, amount } => {
+                let market = self.market_mut(&market)?;
+                market.outgoing_cash += amount;
+                market.pending_borrows += amount;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // перевод прошел или не прошел - отложенное снова свободно
+    pub fn release(&mut self, operation: &Operation) -> Result<(), CompoundError> {
+        match *operation {
+            Operation::Lend { market, amount } => {
+                self.market_mut(&market)?.incoming_supply -= amount;
+            }
+            Operation::Withdraw { market, amount, .. } => {
+                self.market_mut(&market)?.outgoing_cash -= amount;
+            }
+            Operation::Borrow { market, amount } => {
+                let market = self.market_mut(&market)?;
+                market.outgoing_cash -= amount;
+                market.pending_borrows -= amount;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // обновляем таблицы после того, как перевод операции прошел
+    pub fn apply_operation(
+        &mut self,
+        user: ActorId,
+        operation: Operation,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.release(&operation)?; // отложенное в `reserve` теперь учтено в самих таблицах
+        match operation {
+            Operation::Lend {
+                market: market_id,
+                amount,
+            } => {
+                let market = self.market_mut(&market_id)?;
+                // курс берется после перевода: пока он шел, другие действия могли его сдвинуть
+                let ctokens_amount = market.count_ctokens(amount, Rounding::Down);
+                market // обновляем информацию о количестве токенов пользователя в общей таблице
+                    .user_assets
+                    .entry(user)
+                    .and_modify(|assets| assets.add_lend(ctokens_amount))
+                    .or_insert_with(|| Assets::new(ctokens_amount));
+                market.total_cash += amount;
+                market.total_ctokens += ctokens_amount;
+
+                // сообщение о том, что на определенный адрес было записано определенное количество ctokens
+                Ok(CompoundEvent::TokensLended {
+                    market: market_id,
+                    address: user,
+                    amount,
+                    ctokens_amount,
+                })
+            }
+            Operation::Withdraw {
+                market: market_id,
+                amount,
+                ctokens_amount,
+            } => {
+                let market = self.market_mut(&market_id)?;
+                market // обновляем информацию о балансе пользователя
+                    .user_assets
+                    .entry(user)
+                    .and_modify(|assets| assets.sub_lend(ctokens_amount));
+                market.total_cash -= amount;
+                market.total_ctokens -= ctokens_amount;
+
+                // инфа об успешном выводе средств
+                Ok(CompoundEvent::TokensWithdrawed {
+                    market: market_id,
+                    address: user,
+                    amount,
+                })
+            }
+            Operation::Borrow {
+                market: market_id,
+                amount,
+            } => {
+                let market = self.market_mut(&market_id)?;
+                let borrow_index = market.borrow_index;
+                market // обновляем информацию о количестве токенов пользователя в общей таблице
+                    .user_assets
+                    .entry(user)
+                    .or_default()
+                    .add_borrow(amount, borrow_index);
+                market.total_cash -= amount;
+                market.total_borrows += amount;
+
+                // сообщение о том, что на определенный адрес было записано определенное количество токенов
+                Ok(CompoundEvent::TokensBorrowed {
+                    market: market_id,
+                    address: user,
+                    amount,
+                    borrow_rate: market.borrow_rate(),
+                })
+            }
+            Operation::Refund {
+                market: market_id,
+                amount,
+            } => {
+                let market = self.market_mut(&market_id)?;
+                let borrow_index = market.borrow_index;
+                market // обновляем информацию о балансе пользователя
+                    .user_assets
+                    .entry(user)
+                    .and_modify(|assets| assets.sub_borrow(amount, borrow_index));
+                market.total_cash += amount;
+                market.total_borrows = market.total_borrows.saturating_sub(amount); // сумма долгов округляется вниз
+
+                // инфа, что пользователь закрыл задолженность
+                Ok(CompoundEvent::TokensRefunded {
+                    market: market_id,
+                    address: user,
+                    amount,
+                })
+            }
+            Operation::Liquidate {
+                borrower,
+                repay_market: repay_market_id,
+                collateral_market: collateral_market_id,
+                repay_amount,
+                ctokens_seized,
+            } => {
+                let repay_market = self.market_mut(&repay_market_id)?;
+                let borrow_index = repay_market.borrow_index;
+                repay_market // обновляем информацию о долге заемщика
+                    .user_assets
+                    .entry(borrower)
+                    .and_modify(|assets| assets.sub_borrow(repay_amount, borrow_index));
+                repay_market.total_cash += repay_amount;
+                repay_market.total_borrows =
+                    repay_market.total_borrows.saturating_sub(repay_amount);
+
+                let collateral_market = self.market_mut(&collateral_market_id)?;
+                collateral_market // его залоге
+                    .user_assets
+                    .entry(borrower)
+                    .and_modify(|assets| assets.sub_lend(ctokens_seized));
+                collateral_market // и вкладе ликвидатора
+                    .user_assets
+                    .entry(user)
+                    .and_modify(|assets| assets.add_lend(ctokens_seized))
+                    .or_insert_with(|| Assets::new(ctokens_seized));
+
+                // инфа о ликвидации
+                Ok(CompoundEvent::Liquidated {
+                    liquidator: user,
+                    borrower,
+                    repay_market: repay_market_id,
+                    collateral_market: collateral_market_id,
+                    repay_amount,
+                    ctokens_seized,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// резервы протокола: в них копится доля `reserve_factor` процентов заемщиков,
+// администратор может пополнить резервы своими токенами или вывести их
+
+use crate::{asserts, Compound, Pending, Step};
+use compound_io::*;
+use gstd::ActorId;
+
+impl Compound {
+    pub fn add_reserves(
+        &mut self,
+        caller: ActorId,
+        market_id: ActorId,
+        amount: Tokens,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        self.only_admin(caller)?;
+        asserts::greater_zero(amount.0)?;
+        self.pool.accrue_interest(now);
+        let token_address = self.pool.market(&market_id)?.token_address;
+
+        Ok(Step::Transfer {
+            transfer: Transfer {
+                token: token_address,
+                from: caller,
+                to: self.program_id,
+                amount: amount.0,
+            },
+            pending: Pending::AddReserves {
+                market: market_id,
+                amount,
+            },
+        })
+    }
+
+    pub fn reduce_reserves(
+        &mut self,
+        caller: ActorId,
+        market_id: ActorId,
+        amount: Tokens,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        self.only_admin(caller)?;
+        asserts::greater_zero(amount.0)?;
+        self.pool.accrue_interest(now);
+        let market = self.pool.market_mut(&market_id)?;
+        if amount > market.total_reserves {
+            return Err(CompoundError::NotEnoughReserves);
+        }
+        if amount > market.free_cash() {
+            return Err(CompoundError::NotEnoughCash);
+        }
+
+        // списываем резервы до перевода, чтобы пока ждем ответа, эти токены не заняли
+        market.total_cash -= amount;
+        market.total_reserves -= amount;
+
+        Ok(Step::Transfer {
+            transfer: Transfer {
+                token: market.token_address,
+                from: self.program_id,
+                to: caller,
+                amount: amount.0,
+            },
+            pending: Pending::ReduceReserves {
+                market: market_id,
+                amount,
+            },
+        })
+    }
+
+    pub(crate) fn reserves_added(
+        &mut self,
+        market_id: ActorId,
+        amount: Tokens,
+    ) -> Result<CompoundEvent, CompoundError> {
+        let market = self.pool.market_mut(&market_id)?;
+        market.total_cash += amount;
+        market.total_reserves += amount;
+
+        Ok(CompoundEvent::ReservesAdded {
+            market: market_id,
+            amount,
+            total_reserves: market.total_reserves,
+        })
+    }
+
+    pub(crate) fn reserves_reduced(
+        &mut self,
+        market_id: ActorId,
+        amount: Tokens,
+        result: Result<(), CompoundError>,
+    ) -> Result<CompoundEvent, CompoundError> {
+        let market = self.pool.market_mut(&market_id)?;
+        if let Err(error) = result {
+            // перевод не прошел - возвращаем списанные резервы
+            market.total_cash += amount;
+            market.total_reserves += amount;
+            return Err(error);
+        }
+
+        Ok(CompoundEvent::ReservesReduced {
+            market: market_id,
+            amount,
+            total_reserves: market.total_reserves,
+        })
+    }
+}
@@ -0,0 +1,93 @@
+// очередь изменений параметров риска: изменение применяется не раньше, чем через `delay` секунд
+// после постановки в очередь, и не позже `GRACE_PERIOD` после этого; отмененные и примененные
+// предложения остаются в истории
+
+use crate::Compound;
+use compound_io::{timelock::GRACE_PERIOD, *};
+use gstd::{collections::BTreeMap, prelude::*, ActorId};
+
+#[derive(Debug, Default, Clone)]
+pub struct Timelock {
+    pub delay: u64,                         // задержка в секундах
+    pub proposals: BTreeMap<u64, Proposal>, // все предложения по id
+    pub next_proposal_id: u64,              // id следующего предложения
+}
+
+impl Compound {
+    pub fn queue_proposal(
+        &mut self,
+        caller: ActorId,
+        change: ParameterChange,
+        now: u64,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.only_admin(caller)?;
+        self.check_change(&change)?;
+
+        let eta = now + self.timelock.delay;
+        let proposal_id = self.timelock.next_proposal_id;
+        self.timelock.next_proposal_id += 1;
+        self.timelock.proposals.insert(
+            proposal_id,
+            Proposal {
+                change: change.clone(),
+                queued_at: now,
+                eta,
+                status: ProposalStatus::Queued,
+            },
+        );
+
+        Ok(CompoundEvent::ProposalQueued {
+            proposal_id,
+            change,
+            eta,
+        })
+    }
+
+    pub fn cancel_proposal(
+        &mut self,
+        caller: ActorId,
+        proposal_id: u64,
+        now: u64,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.only_admin(caller)?;
+        let proposal = self.queued_proposal(proposal_id)?;
+        proposal.status = ProposalStatus::Cancelled { at: now };
+
+        Ok(CompoundEvent::ProposalCancelled { proposal_id })
+    }
+
+    pub fn execute_proposal(
+        &mut self,
+        caller: ActorId,
+        proposal_id: u64,
+        now: u64,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.only_admin(caller)?;
+        let proposal = self.queued_proposal(proposal_id)?;
+        if now < proposal.eta {
+            return Err(CompoundError::ProposalNotReady(proposal_id));
+        }
+        if now > proposal.eta.saturating_add(GRACE_PERIOD) {
+            return Err(CompoundError::ProposalExpired(proposal_id));
+        }
+        let change = proposal.change.clone();
+
+        let event = self.apply_change(change, now)?;
+        if let Some(proposal) = self.timelock.proposals.get_mut(&proposal_id) {
+            proposal.status = ProposalStatus::Executed { at: now };
+        }
+        Ok(event)
+    }
+
+    fn queued_proposal(&mut self, proposal_id: u64) -> Result<&mut Proposal, CompoundError> {
+        let proposal = self
+            .timelock
+            .proposals
+            .get_mut(&proposal_id)
+            .ok_or(CompoundError::ProposalNotFound(proposal_id))?;
+        if proposal.status != ProposalStatus::Queued {
+            return Err(CompoundError::ProposalNotQueued(proposal_id));
+        }
+        Ok(proposal)
+    }
+}
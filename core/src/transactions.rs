@@ -0,0 +1,99 @@
+// журнал операций: операция записывается в него перед переводом токенов и убирается, когда
+// пришел ответ. Если сообщение прервалось после отправки перевода, неизвестно, ушли ли токены,
+// поэтому операция остается в журнале зависшей вместе с отложенными под нее токенами
+// и блокировками счетов, пока администратор не сверит перевод с контрактом токена
+
+use crate::{Compound, Pending, Step};
+use compound_io::*;
+use gstd::{prelude::*, ActorId};
+
+impl Compound {
+    pub(crate) fn start_transaction(
+        &mut self,
+        user: ActorId,
+        operation: Operation,
+        transfer: Transfer,
+        locked: Vec<ActorId>,
+    ) -> Step {
+        let tx_id = self.next_tx_id;
+        self.next_tx_id += 1;
+        self.transactions.insert(
+            tx_id,
+            Transaction::new(user, operation, transfer.clone(), locked),
+        );
+
+        Step::Transfer {
+            transfer,
+            pending: Pending::Transaction(tx_id),
+        }
+    }
+
+    pub(crate) fn transaction_transferred(
+        &mut self,
+        tx_id: u64,
+        result: Result<(), CompoundError>,
+        now: u64,
+    ) -> Result<Step, CompoundError> {
+        let tx = self
+            .transactions
+            .remove(&tx_id)
+            .ok_or(CompoundError::TransactionNotFound(tx_id))?;
+        self.unlock_accounts(&tx.locked);
+        if let Err(error) = result {
+            self.pool.release(&tx.operation)?;
+            return Err(error);
+        }
+        self.pool.accrue_interest(now);
+        self.pool
+            .apply_operation(tx.user, tx.operation)
+            .map(Step::Done)
+    }
+
+    // сообщение с действием прервалось после ожидания ответа: операции этих счетов, чей перевод
+    // еще не подтвержден, становятся зависшими и держат все счета действия, остальные счета
+    // освобождаются
+    pub fn abort_action(&mut self, accounts: &[ActorId]) {
+        let stuck = self
+            .transactions
+            .values_mut()
+            .find(|tx| tx.status == TransactionStatus::InProgress && accounts.contains(&tx.user));
+        match stuck {
+            Some(tx) => {
+                tx.status = TransactionStatus::Stuck;
+                tx.locked
+                    .extend(accounts.iter().filter(|account| **account != tx.user));
+            }
+            None => self.unlock_accounts(accounts),
+        }
+    }
+
+    pub fn resolve_transaction(
+        &mut self,
+        caller: ActorId,
+        tx_id: u64,
+        transferred: bool,
+        now: u64,
+    ) -> Result<CompoundEvent, CompoundError> {
+        self.only_admin(caller)?;
+        let tx = self
+            .transactions
+            .get(&tx_id)
+            .cloned()
+            .ok_or(CompoundError::TransactionNotFound(tx_id))?;
+        if tx.status == TransactionStatus::InProgress {
+            return Err(CompoundError::TransactionInProgress(tx_id));
+        }
+
+        if transferred {
+            self.pool.accrue_interest(now);
+            self.pool.apply_operation(tx.user, tx.operation)?;
+        } else {
+            self.pool.release(&tx.operation)?;
+        }
+        self.transactions.remove(&tx_id);
+        self.unlock_accounts(&[tx.user]);
+        self.unlock_accounts(&tx.locked);
+
+        Ok(CompoundEvent::TransactionResolved { tx_id, transferred })
+    }
+}
@@ -0,0 +1,600 @@
+use compound_core::{Compound, Pending, Step};
+use compound_io::{
+    ft::{FTAction, FTEvent},
+    *,
+};
+use gstd::ActorId;
+
+const PROGRAM: u64 = 1;
+const ADMIN: u64 = 2;
+const USER: u64 = 3;
+const TOKEN: u64 = 10;
+const ORACLE: u64 = 20;
+const CTOKEN: u64 = 30;
+
+fn market() -> MarketConfig {
+    MarketConfig {
+        token_address: TOKEN.into(),
+        collateral_factor: Wad::from_percent(50),
+        rate_model: RateModel::Linear(LinearRateModel {
+            base_rate: Wad::from_percent(2),
+            multiplier: Wad::from_percent(20),
+        }),
+        ctoken_rate: Wad::ONE,
+        fallback_price: Wad::ONE,
+        ..Default::default()
+    }
+}
+
+fn compound(oracle: u64) -> Compound {
+    let mut compound = Compound::new(
+        CompoundInit {
+            close_factor: Wad::from_percent(50),
+            liquidation_incentive: Wad::from_percent(8),
+            oracle: oracle.into(),
+            max_price_age: 60,
+            timelock_delay: timelock::MIN_TIMELOCK_DELAY,
+            markets: vec![market()],
+            ..Default::default()
+        },
+        ADMIN.into(),
+        PROGRAM.into(),
+        0,
+    )
+    .expect("valid config");
+    // адаптер создает контракт ctoken рынка и сообщает его адрес
+    compound
+        .ctoken_created(TOKEN.into(), CTOKEN.into())
+        .unwrap();
+    compound
+}
+
+fn transfer(step: Step) -> (Transfer, Pending) {
+    match step {
+        Step::Transfer { transfer, pending } => (transfer, pending),
+        step => panic!("Expected transfer, got {step:?}"),
+    }
+}
+
+fn done(step: Step) -> CompoundEvent {
+    match step {
+        Step::Done(event) => event,
+        step => panic!("Expected event, got {step:?}"),
+    }
+}
+
+// вклад: токены пользователя на контракт, ctokens выпускаются только после перевода
+fn lend(compound: &mut Compound, amount: u128) -> CompoundEvent {
+    let action = CompoundAction::LendTokens {
+        market: TOKEN.into(),
+        amount: Tokens(amount),
+    };
+    let (tokens_in, pending) = transfer(compound.apply(action, USER.into(), 0).unwrap());
+    assert_eq!(
+        tokens_in,
+        Transfer {
+            token: TOKEN.into(),
+            from: USER.into(),
+            to: PROGRAM.into(),
+            amount,
+        }
+    );
+    assert!(compound
+        .pool
+        .market(&TOKEN.into())
+        .unwrap()
+        .user_assets
+        .is_empty());
+
+    done(compound.transferred(pending, Ok(()), 0).unwrap())
+}
+
+#[test]
+fn lend_runs_transfers_before_accounting() {
+    let mut compound = compound(0);
+    let event = lend(&mut compound, 1000);
+
+    assert_eq!(
+        event,
+        CompoundEvent::TokensLended {
+            market: TOKEN.into(),
+            address: USER.into(),
+            amount: Tokens(1000),
+            ctokens_amount: CTokens(1000),
+        }
+    );
+    let market = compound.pool.market(&TOKEN.into()).unwrap();
+    assert_eq!(market.total_cash, Tokens(1000));
+}
+
+#[test]
+fn lend_mints_at_rate_after_transfer() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let borrow = CompoundAction::BorrowTokens {
+        market: TOKEN.into(),
+        amount: Tokens(400),
+    };
+    let (_, pending) = transfer(compound.apply(borrow, USER.into(), 0).unwrap());
+    done(compound.transferred(pending, Ok(()), 0).unwrap());
+
+    // пока перевод вклада шел, по кредиту набежали проценты и курс ctoken вырос
+    let action = CompoundAction::LendTokens {
+        market: TOKEN.into(),
+        amount: Tokens(1000),
+    };
+    let (_, pending) = transfer(compound.apply(action, ADMIN.into(), 0).unwrap());
+    let event = done(
+        compound
+            .transferred(pending, Ok(()), 365 * 24 * 3600)
+            .unwrap(),
+    );
+    let rate = compound.pool.market(&TOKEN.into()).unwrap().exchange_rate();
+    let CompoundEvent::TokensLended { ctokens_amount, .. } = event else {
+        panic!("Expected lend, got {event:?}");
+    };
+    assert!(ctokens_amount < CTokens(1000));
+    assert!(rate.scale(ctokens_amount.0, Rounding::Down) <= 1000);
+    assert!(rate.scale(ctokens_amount.0 + 1, Rounding::Up) > 1000);
+}
+
+#[test]
+fn failed_transfer_mints_nothing() {
+    let mut compound = compound(0);
+    let action = CompoundAction::LendTokens {
+        market: TOKEN.into(),
+        amount: Tokens(1000),
+    };
+    let (_, pending) = transfer(compound.apply(action, USER.into(), 0).unwrap());
+
+    assert_eq!(
+        compound
+            .transferred(pending, Err(CompoundError::TransferFailed), 0)
+            .unwrap_err(),
+        CompoundError::TransferFailed
+    );
+    let market = compound.pool.market(&TOKEN.into()).unwrap();
+    assert!(market.user_assets.is_empty());
+    assert_eq!(market.total_ctokens, CTokens(0));
+}
+
+#[test]
+fn withdraw_reserves_cash_until_transfer() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let withdraw = |amount| CompoundAction::WithdrawTokens {
+        market: TOKEN.into(),
+        amount: Tokens(amount),
+    };
+    let free_cash = |compound: &Compound| compound.pool.market(&TOKEN.into()).unwrap().free_cash();
+
+    // пока перевод не подтвержден, токены отложены и другие проверки их не видят,
+    // а курс ctoken не меняется
+    let (_, pending) = transfer(compound.apply(withdraw(600), USER.into(), 0).unwrap());
+    assert_eq!(free_cash(&compound), Tokens(400));
+    assert_eq!(
+        compound.pool.market(&TOKEN.into()).unwrap().exchange_rate(),
+        Wad::ONE
+    );
+    assert_eq!(
+        compound.apply(withdraw(500), USER.into(), 0).unwrap_err(),
+        CompoundError::NotEnoughCash
+    );
+
+    assert_eq!(
+        compound
+            .transferred(pending, Err(CompoundError::TransferFailed), 0)
+            .unwrap_err(),
+        CompoundError::TransferFailed
+    );
+    assert_eq!(free_cash(&compound), Tokens(1000));
+
+    let (_, pending) = transfer(compound.apply(withdraw(600), USER.into(), 0).unwrap());
+    done(compound.transferred(pending, Ok(()), 0).unwrap());
+    let market = compound.pool.market(&TOKEN.into()).unwrap();
+    assert_eq!(market.total_cash, Tokens(400));
+    assert_eq!(market.total_ctokens, CTokens(400));
+}
+
+#[test]
+fn pending_operations_count_against_caps() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let market = compound.pool.market_mut(&TOKEN.into()).unwrap();
+    market.supply_cap = Tokens(1500);
+    market.borrow_cap = Tokens(500);
+    let lend = |amount| CompoundAction::LendTokens {
+        market: TOKEN.into(),
+        amount: Tokens(amount),
+    };
+    let borrow = |amount| CompoundAction::BorrowTokens {
+        market: TOKEN.into(),
+        amount: Tokens(amount),
+    };
+
+    // вклад и кредит еще переводятся, но уже занимают место под пределами
+    let (_, lend_pending) = transfer(compound.apply(lend(400), USER.into(), 0).unwrap());
+    assert_eq!(
+        compound.apply(lend(200), ADMIN.into(), 0).unwrap_err(),
+        CompoundError::SupplyCapExceeded(TOKEN.into())
+    );
+    let (_, borrow_pending) = transfer(compound.apply(borrow(300), USER.into(), 0).unwrap());
+    assert_eq!(
+        compound.apply(borrow(300), USER.into(), 0).unwrap_err(),
+        CompoundError::BorrowCapExceeded(TOKEN.into())
+    );
+    assert_eq!(
+        compound.pool.market(&TOKEN.into()).unwrap().borrow_room(),
+        Some(Tokens(200))
+    );
+
+    // непрошедший перевод освобождает место, прошедший переносит его в таблицы
+    assert_eq!(
+        compound
+            .transferred(lend_pending, Err(CompoundError::TransferFailed), 0)
+            .unwrap_err(),
+        CompoundError::TransferFailed
+    );
+    transfer(compound.apply(lend(500), ADMIN.into(), 0).unwrap());
+    done(compound.transferred(borrow_pending, Ok(()), 0).unwrap());
+    let market = compound.pool.market(&TOKEN.into()).unwrap();
+    assert_eq!(market.pending_borrows, Tokens(0));
+    assert_eq!(market.borrow_room(), Some(Tokens(200)));
+}
+
+// действие, которое контракт ctoken рынка пересылает от пользователя `sender`
+fn ctoken(compound: &mut Compound, sender: u64, action: FTAction) -> Result<Step, CompoundError> {
+    compound.apply(
+        CompoundAction::CToken {
+            sender: sender.into(),
+            action,
+        },
+        CTOKEN.into(),
+        0,
+    )
+}
+
+fn ctoken_balance(compound: &mut Compound, account: u64) -> u128 {
+    match done(ctoken(compound, ADMIN, FTAction::BalanceOf(account.into())).unwrap()) {
+        CompoundEvent::CToken {
+            event: FTEvent::Balance(balance),
+            ..
+        } => balance,
+        event => panic!("Unexpected event: {event:?}"),
+    }
+}
+
+#[test]
+fn ctokens_transfer_by_allowance() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let transfer = |amount| FTAction::Transfer {
+        from: USER.into(),
+        to: ADMIN.into(),
+        amount,
+    };
+
+    assert_eq!(
+        ctoken(&mut compound, ADMIN, transfer(300)).unwrap_err(),
+        CompoundError::NotEnoughAllowance
+    );
+    ctoken(
+        &mut compound,
+        USER,
+        FTAction::Approve {
+            to: ADMIN.into(),
+            amount: 300,
+        },
+    )
+    .unwrap();
+    done(ctoken(&mut compound, ADMIN, transfer(300)).unwrap());
+
+    assert_eq!(ctoken_balance(&mut compound, USER), 700);
+    assert_eq!(ctoken_balance(&mut compound, ADMIN), 300);
+    assert_eq!(
+        ctoken(&mut compound, ADMIN, transfer(1)).unwrap_err(),
+        CompoundError::NotEnoughAllowance
+    );
+    assert_eq!(
+        ctoken(&mut compound, USER, FTAction::Mint(1)).unwrap_err(),
+        CompoundError::Unauthorized
+    );
+}
+
+#[test]
+fn ctoken_actions_only_from_ctoken() {
+    let mut compound = compound(ORACLE);
+    lend(&mut compound, 1000);
+    let action = CompoundAction::CToken {
+        sender: ADMIN.into(),
+        action: FTAction::Transfer {
+            from: USER.into(),
+            to: ADMIN.into(),
+            amount: 100,
+        },
+    };
+
+    // чужой адрес не блокирует счета и не ждет цен оракула
+    assert!(compound.action_accounts(&action, ADMIN.into()).is_empty());
+    assert_eq!(
+        compound.apply(action.clone(), ADMIN.into(), 0).unwrap_err(),
+        CompoundError::Unauthorized
+    );
+    assert_eq!(
+        compound.action_accounts(&action, CTOKEN.into()),
+        vec![ActorId::from(USER), ActorId::from(ADMIN)]
+    );
+    assert_eq!(ctoken_balance(&mut compound, USER), 1000);
+}
+
+#[test]
+fn liquidation_locks_borrower_after_check() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let action = CompoundAction::BorrowTokens {
+        market: TOKEN.into(),
+        amount: Tokens(400),
+    };
+    let (_, pending) = transfer(compound.apply(action, USER.into(), 0).unwrap());
+    done(compound.transferred(pending, Ok(()), 0).unwrap());
+    let liquidate = CompoundAction::Liquidate {
+        borrower: USER.into(),
+        repay_market: TOKEN.into(),
+        collateral_market: TOKEN.into(),
+        repay_amount: Tokens(100),
+    };
+
+    // до проверки залога ликвидатор блокирует только свой счет
+    assert_eq!(
+        compound.action_accounts(&liquidate, ADMIN.into()),
+        vec![ActorId::from(ADMIN)]
+    );
+    assert_eq!(
+        compound
+            .apply(liquidate.clone(), ADMIN.into(), 0)
+            .unwrap_err(),
+        CompoundError::NotUndercollateralized(USER.into())
+    );
+    assert!(compound.locked_accounts.is_empty());
+
+    compound
+        .pool
+        .market_mut(&TOKEN.into())
+        .unwrap()
+        .collateral_factor = Wad::from_percent(30);
+    compound.lock_accounts(&[USER.into()]).unwrap();
+    assert_eq!(
+        compound
+            .apply(liquidate.clone(), ADMIN.into(), 0)
+            .unwrap_err(),
+        CompoundError::AccountLocked(USER.into())
+    );
+    compound.unlock_accounts(&[USER.into()]);
+
+    let (_, pending) = transfer(compound.apply(liquidate, ADMIN.into(), 0).unwrap());
+    assert!(compound.locked_accounts.contains(&USER.into()));
+    assert!(matches!(
+        done(compound.transferred(pending, Ok(()), 0).unwrap()),
+        CompoundEvent::Liquidated { .. }
+    ));
+    assert!(compound.locked_accounts.is_empty());
+}
+
+#[test]
+fn ctokens_transfer_keeps_debt_covered() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let action = CompoundAction::BorrowTokens {
+        market: TOKEN.into(),
+        amount: Tokens(400),
+    };
+    let (_, pending) = transfer(compound.apply(action, USER.into(), 0).unwrap());
+    done(compound.transferred(pending, Ok(()), 0).unwrap());
+
+    // под долг 400 при коэффициенте 50% нужно 800 токенов залога
+    let transfer = |amount| FTAction::Transfer {
+        from: USER.into(),
+        to: ADMIN.into(),
+        amount,
+    };
+    assert_eq!(
+        ctoken(&mut compound, USER, transfer(201)).unwrap_err(),
+        CompoundError::InsufficientCollateral
+    );
+    done(ctoken(&mut compound, USER, transfer(200)).unwrap());
+    assert_eq!(ctoken_balance(&mut compound, ADMIN), 200);
+}
+
+#[test]
+fn borrow_waits_for_oracle_prices() {
+    let mut compound = compound(ORACLE);
+    lend(&mut compound, 1000);
+    let action = CompoundAction::BorrowTokens {
+        market: TOKEN.into(),
+        amount: Tokens(400),
+    };
+
+    let Step::FetchPrices {
+        oracle,
+        tokens,
+        action,
+        caller,
+    } = compound.apply(action, USER.into(), 10).unwrap()
+    else {
+        panic!("Expected price request");
+    };
+    assert_eq!(oracle, ORACLE.into());
+    assert_eq!(tokens, vec![ActorId::from(TOKEN)]);
+
+    // оракул не ответил - берется резервная цена
+    let step = compound
+        .prices_fetched(action.clone(), caller, vec![(TOKEN.into(), None)], 10)
+        .unwrap();
+    let (borrowed, pending) = transfer(step);
+    assert_eq!(borrowed.to, USER.into());
+
+    assert_eq!(
+        compound
+            .transferred(pending, Err(CompoundError::TransferFailed), 0)
+            .unwrap_err(),
+        CompoundError::TransferFailed
+    );
+    assert_eq!(
+        compound.pool.market(&TOKEN.into()).unwrap().total_borrows,
+        Tokens(0)
+    );
+
+    let step = compound
+        .prices_fetched(action, caller, vec![(TOKEN.into(), None)], 10)
+        .unwrap();
+    let (_, pending) = transfer(step);
+    assert!(matches!(
+        done(compound.transferred(pending, Ok(()), 0).unwrap()),
+        CompoundEvent::TokensBorrowed { .. }
+    ));
+}
+
+#[test]
+fn stale_price_without_fallback() {
+    let mut compound = compound(ORACLE);
+    compound
+        .pool
+        .market_mut(&TOKEN.into())
+        .unwrap()
+        .fallback_price = Wad(0);
+    let action = CompoundAction::ExitMarket {
+        market: TOKEN.into(),
+    };
+
+    assert_eq!(
+        compound
+            .prices_fetched(
+                action,
+                USER.into(),
+                vec![(TOKEN.into(), Some((Wad::ONE, 0)))],
+                100
+            )
+            .unwrap_err(),
+        CompoundError::PriceUnavailable(TOKEN.into())
+    );
+}
+
+#[test]
+fn markets_are_added_through_timelock() {
+    let mut compound = compound(0);
+    let queue = CompoundAction::QueueProposal {
+        change: ParameterChange::AddMarket(MarketConfig {
+            token_address: 12.into(),
+            ..market()
+        }),
+    };
+
+    assert_eq!(
+        compound.apply(queue.clone(), USER.into(), 0).unwrap_err(),
+        CompoundError::Unauthorized
+    );
+    let CompoundEvent::ProposalQueued {
+        proposal_id, eta, ..
+    } = done(compound.apply(queue, ADMIN.into(), 0).unwrap())
+    else {
+        panic!("Expected queued proposal");
+    };
+    assert_eq!(eta, timelock::MIN_TIMELOCK_DELAY);
+
+    let execute = CompoundAction::ExecuteProposal { proposal_id };
+    assert_eq!(
+        compound
+            .apply(execute.clone(), ADMIN.into(), eta - 1)
+            .unwrap_err(),
+        CompoundError::ProposalNotReady(proposal_id)
+    );
+    let Step::CreateCToken { code, market } = compound.apply(execute, ADMIN.into(), eta).unwrap()
+    else {
+        panic!("Expected ctoken creation");
+    };
+    assert_eq!((code, market), (compound.ctoken_code, 12.into()));
+    assert_eq!(
+        done(compound.ctoken_created(market, 31.into()).unwrap()),
+        CompoundEvent::MarketAdded { market: 12.into() }
+    );
+    assert_eq!(compound.pool.market(&market).unwrap().ctoken, 31.into());
+}
+
+#[test]
+fn interrupted_transfer_waits_for_admin() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let withdraw = CompoundAction::WithdrawTokens {
+        market: TOKEN.into(),
+        amount: Tokens(600),
+    };
+    let accounts = compound.action_accounts(&withdraw, USER.into());
+    compound.lock_accounts(&accounts).unwrap();
+    let (_, pending) = transfer(compound.apply(withdraw, USER.into(), 0).unwrap());
+    assert_eq!(pending, Pending::Transaction(1));
+    let resolve = |transferred| CompoundAction::ResolveTransaction {
+        tx_id: 1,
+        transferred,
+    };
+    assert_eq!(
+        compound.apply(resolve(false), ADMIN.into(), 0).unwrap_err(),
+        CompoundError::TransactionInProgress(1)
+    );
+
+    // сообщение прервалось до ответа на перевод: операция остается в журнале, токены отложены,
+    // а счет заблокирован, пока администратор не сверит перевод
+    compound.abort_action(&accounts);
+    assert_eq!(compound.transactions[&1].status, TransactionStatus::Stuck);
+    assert!(compound.locked_accounts.contains(&USER.into()));
+    let free_cash = |compound: &Compound| compound.pool.market(&TOKEN.into()).unwrap().free_cash();
+    assert_eq!(free_cash(&compound), Tokens(400));
+
+    assert_eq!(
+        compound.apply(resolve(true), USER.into(), 0).unwrap_err(),
+        CompoundError::Unauthorized
+    );
+    assert_eq!(
+        done(compound.apply(resolve(true), ADMIN.into(), 0).unwrap()),
+        CompoundEvent::TransactionResolved {
+            tx_id: 1,
+            transferred: true,
+        }
+    );
+    let market = compound.pool.market(&TOKEN.into()).unwrap();
+    assert_eq!(market.total_cash, Tokens(400));
+    assert_eq!(market.free_cash(), Tokens(400));
+    assert_eq!(market.total_ctokens, CTokens(400));
+    assert!(compound.transactions.is_empty());
+    assert!(compound.locked_accounts.is_empty());
+}
+
+#[test]
+fn failed_interrupted_transfer_releases_cash() {
+    let mut compound = compound(0);
+    lend(&mut compound, 1000);
+    let borrow = CompoundAction::BorrowTokens {
+        market: TOKEN.into(),
+        amount: Tokens(300),
+    };
+    let accounts = compound.action_accounts(&borrow, USER.into());
+    compound.lock_accounts(&accounts).unwrap();
+    transfer(compound.apply(borrow, USER.into(), 0).unwrap());
+    compound.abort_action(&accounts);
+
+    done(
+        compound
+            .apply(
+                CompoundAction::ResolveTransaction {
+                    tx_id: 1,
+                    transferred: false,
+                },
+                ADMIN.into(),
+                0,
+            )
+            .unwrap(),
+    );
+    let market = compound.pool.market(&TOKEN.into()).unwrap();
+    assert_eq!(market.free_cash(), Tokens(1000));
+    assert_eq!(market.total_borrows, Tokens(0));
+    assert!(compound.locked_accounts.is_empty());
+}
@@ -0,0 +1,56 @@
+#![no_std]
+
+// ctoken одного рынка с интерфейсом стандартного фунгибельного токена Gear: кошельки и другие
+// контракты шлют сюда обычные `FTAction` и получают `FTEvent`. Балансы ведет контракт compound,
+// который и создает этот контракт при открытии рынка, поэтому действие пересылается ему от имени
+// отправителя, а ошибка, как и у fungible-token, завершает сообщение паникой
+
+use compound_io::{
+    ft::{FTAction, FTEvent},
+    CompoundAction, CompoundError, CompoundEvent,
+};
+use core::cell::Cell;
+use gstd::{msg, prelude::*, ActorId};
+
+// собранный контракт для тестов на gtest
+#[cfg(not(target_arch = "wasm32"))]
+include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+
+// контракт compound, который создал этот ctoken; задается один раз в `init`
+struct Creator(Cell<ActorId>);
+
+// программа Gear выполняется в одном потоке, а адрес меняется только в `init`, до любых сообщений
+unsafe impl Sync for Creator {}
+
+static COMPOUND: Creator = Creator(Cell::new(ActorId::zero()));
+
+#[gstd::async_main]
+async fn main() {
+    let action: FTAction = msg::load().expect("Unable to decode FTAction");
+    let compound = COMPOUND.0.get();
+
+    let reply: Result<CompoundEvent, CompoundError> = msg::send_for_reply_as(
+        compound,
+        CompoundAction::CToken {
+            sender: msg::source(),
+            action,
+        },
+        0,
+        0,
+    )
+    .expect("Error in sending a message")
+    .await
+    .expect("Unable to get reply from compound");
+
+    let event: FTEvent = match reply {
+        Ok(CompoundEvent::CToken { event, .. }) => event,
+        Ok(event) => panic!("Unexpected event: {:?}", event),
+        Err(error) => panic!("{:?}", error),
+    };
+    msg::reply(event, 0).expect("Error in reply");
+}
+
+#[no_mangle]
+extern "C" fn init() {
+    COMPOUND.0.set(msg::source());
+}
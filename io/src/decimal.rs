@@ -0,0 +1,152 @@
+// числа с фиксированной точкой для ставок, курсов, индексов, цен и коэффициентов.
+// `Wad` хранит число, умноженное на 10^18: `Wad::ONE` - это 1, 100% или 100% годовых.
+// Каждое умножение и деление явно указывает, куда округлять: округление всегда идет
+// в пользу протокола (вкладчик получает чуть меньше, заемщик должен чуть больше)
+
+use core::ops::{Add, Sub};
+use gstd::prelude::*;
+
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Wad(pub u128);
+
+impl Wad {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(WAD);
+    pub const MAX: Self = Self(u128::MAX);
+
+    // `percent` процентов, например `from_percent(75)` - это 0.75
+    pub const fn from_percent(percent: u128) -> Self {
+        Self(percent * (WAD / 100))
+    }
+
+    // `numerator / denominator`, `None` - если результат не влезает
+    pub fn checked_from_ratio(
+        numerator: u128,
+        denominator: u128,
+        rounding: Rounding,
+    ) -> Option<Self> {
+        checked_mul_div(numerator, WAD, denominator, rounding).map(Self)
+    }
+
+    pub fn from_ratio(numerator: u128, denominator: u128, rounding: Rounding) -> Self {
+        Self::checked_from_ratio(numerator, denominator, rounding).expect("Ratio overflow")
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_mul(self, other: Self, rounding: Rounding) -> Option<Self> {
+        checked_mul_div(self.0, other.0, WAD, rounding).map(Self)
+    }
+
+    pub fn checked_div(self, other: Self, rounding: Rounding) -> Option<Self> {
+        checked_mul_div(self.0, WAD, other.0, rounding).map(Self)
+    }
+
+    pub fn mul(self, other: Self, rounding: Rounding) -> Self {
+        self.checked_mul(other, rounding)
+            .expect("Multiplication overflow")
+    }
+
+    pub fn div(self, other: Self, rounding: Rounding) -> Self {
+        self.checked_div(other, rounding)
+            .expect("Division overflow")
+    }
+
+    // `amount * self` для целой суммы, например стоимость токенов по цене
+    pub fn scale(self, amount: u128, rounding: Rounding) -> u128 {
+        mul_div(amount, self.0, WAD, rounding)
+    }
+
+    // `amount / self` для целой суммы, например количество токенов на сумму по цене
+    pub fn unscale(self, amount: u128, rounding: Rounding) -> u128 {
+        mul_div(amount, WAD, self.0, rounding)
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Add for Wad {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0.checked_add(other.0).expect("Addition overflow"))
+    }
+}
+
+impl Sub for Wad {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0.checked_sub(other.0).expect("Subtraction underflow"))
+    }
+}
+
+// `a * b / c` с 256-битным промежуточным произведением, `None` - если результат не влезает в u128
+pub fn checked_mul_div(a: u128, b: u128, c: u128, rounding: Rounding) -> Option<u128> {
+    assert!(c != 0, "Division by zero");
+    let (quotient, remainder) = match a.checked_mul(b) {
+        Some(product) => (product / c, product % c),
+        None => {
+            let (high, low) = widening_mul(a, b);
+            if high >= c {
+                return None;
+            }
+
+            // делим 256-битное число (high, low) на `c` столбиком по одному биту
+            let (mut remainder, mut quotient) = (high, 0u128);
+            for bit in (0..128).rev() {
+                let carry = remainder >> 127;
+                remainder = (remainder << 1) | ((low >> bit) & 1);
+                quotient <<= 1;
+                if carry == 1 || remainder >= c {
+                    remainder = remainder.wrapping_sub(c);
+                    quotient |= 1;
+                }
+            }
+            (quotient, remainder)
+        }
+    };
+
+    match rounding {
+        Rounding::Up if remainder != 0 => quotient.checked_add(1),
+        _ => Some(quotient),
+    }
+}
+
+// `a * b / c`, паникует, только если не влезает результат
+pub fn mul_div(a: u128, b: u128, c: u128, rounding: Rounding) -> u128 {
+    checked_mul_div(a, b, c, rounding).expect("Multiplication overflow")
+}
+
+// полное произведение двух u128 в виде (старшие, младшие) 128 бит
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (low_high & MASK) + (high_low & MASK);
+    let low = (low_low & MASK) | (middle << 64);
+    let high = high_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+    (high, low)
+}
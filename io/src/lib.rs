@@ -0,0 +1,578 @@
+#![no_std]
+
+// типы сообщений контракта, кодируемые в SCALE (общие для контракта, state-крейта и клиентов)
+
+use ft::{FTAction, FTEvent};
+use gmeta::{In, InOut, Metadata};
+use gstd::{prelude::*, ActorId, CodeId};
+
+pub mod decimal;
+pub mod ft;
+pub mod liquidity;
+pub mod operation;
+pub mod oracle;
+pub mod rate_model;
+pub mod timelock;
+pub mod units;
+
+pub use decimal::{checked_mul_div, mul_div, Rounding, Wad};
+pub use liquidity::{AccountLiquidity, MarketPosition};
+pub use operation::{Operation, Transaction, TransactionStatus, Transfer};
+pub use rate_model::{
+    InterestRateModel, JumpRateModel, LinearRateModel, RateModel, MAX_BORROW_RATE,
+};
+pub use timelock::{ParameterChange, Proposal, ProposalStatus};
+pub use units::{CTokens, Tokens};
+
+#[derive(Debug, Default, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct CompoundInit {
+    pub close_factor: Wad, // какую часть долга можно погасить за одну ликвидацию
+    pub liquidation_incentive: Wad, // бонус ликвидатора в долях от погашенного долга
+    pub oracle: ActorId,   // id оракула цен, без оракула используются резервные цены рынков
+    pub max_price_age: u64, // через сколько секунд цена оракула считается устаревшей
+    pub pause_guardian: ActorId, // кто может приостанавливать действия в рынках, нулевой - никто, кроме администратора
+    pub timelock_delay: u64, // через сколько секунд после постановки в очередь можно применить изменение параметров
+    pub markets: Vec<MarketConfig>, // рынки, которые открываются сразу при инициализации
+    pub ctoken_code: CodeId, // загруженный код контракта ctoken, из него для каждого рынка создается свой ctoken
+}
+
+// параметры одного рынка: токен со своей моделью ставки и залоговым коэффициентом; балансы ctokens
+// рынка ведет сам контракт, а контракт ctoken рынка он создает при открытии рынка
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct MarketConfig {
+    pub token_address: ActorId, // id контракта токена, он же id рынка
+    pub collateral_factor: Wad, // какую долю вклада можно занять под залог, не больше `MAX_COLLATERAL_FACTOR`
+    pub rate_model: RateModel,  // модель ставки по кредиту
+    pub reserve_factor: Wad,    // доля процентов заемщиков, которая уходит в резервы протокола
+    pub supply_cap: Tokens,     // предел вкладов в рынке вместе с процентами, 0 - без предела
+    pub borrow_cap: Tokens,     // предел кредитов в рынке вместе с процентами, 0 - без предела
+    pub ctoken_rate: Wad, // сколько ctokens выдается за один токен, пока ctokens еще не выпущены
+    pub fallback_price: Wad, // цена, если оракул не ответил или цена устарела, 0 - резервной цены нет
+}
+
+// действия, которые можно приостановить в отдельном рынке; погашение кредитов не приостанавливается
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum PausableAction {
+    Lend,
+    Borrow,
+    Withdraw,
+    // ликвидация приостанавливается и в рынке долга, и в рынке залога
+    Liquidate,
+}
+
+// рынок во всех действиях задается адресом его токена
+#[derive(Debug, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum CompoundAction {
+    // открыть или пополнить вклад
+    LendTokens {
+        market: ActorId,
+        amount: Tokens,
+    },
+    // взять кредит под залог всех включенных рынков
+    BorrowTokens {
+        market: ActorId,
+        amount: Tokens,
+    },
+    // погасить кредит
+    RefundTokens {
+        market: ActorId,
+        amount: Tokens,
+    },
+    // снять деньги со вклада
+    WithdrawTokens {
+        market: ActorId,
+        amount: Tokens,
+    },
+    // погасить часть чужого долга в `repay_market` и забрать залог в `collateral_market`
+    Liquidate {
+        borrower: ActorId,
+        repay_market: ActorId,
+        collateral_market: ActorId,
+        repay_amount: Tokens,
+    },
+    // учитывать вклад в рынке как залог
+    EnterMarket {
+        market: ActorId,
+    },
+    // перестать учитывать вклад в рынке как залог
+    ExitMarket {
+        market: ActorId,
+    },
+    // поставить изменение параметров риска в очередь (только администратор)
+    QueueProposal {
+        change: ParameterChange,
+    },
+    // отменить изменение из очереди (только администратор)
+    CancelProposal {
+        proposal_id: u64,
+    },
+    // применить изменение после задержки (только администратор),
+    // в ответ приходит событие примененного изменения
+    ExecuteProposal {
+        proposal_id: u64,
+    },
+    // предложить нового администратора, права перейдут, когда он примет их через `AcceptAdmin`
+    TransferAdmin {
+        new_admin: ActorId,
+    },
+    // принять права администратора (только предложенный администратор)
+    AcceptAdmin,
+    // пополнить резервы рынка токенами администратора (только администратор)
+    AddReserves {
+        market: ActorId,
+        amount: Tokens,
+    },
+    // вывести резервы рынка администратору (только администратор)
+    ReduceReserves {
+        market: ActorId,
+        amount: Tokens,
+    },
+    // приостановить действие в рынке (хранитель паузы или администратор)
+    // или возобновить его (только администратор)
+    SetPaused {
+        market: ActorId,
+        action: PausableAction,
+        paused: bool,
+    },
+    // уладить зависшую операцию (только администратор): `transferred` - прошел ли ее перевод
+    // по данным контракта токена; если прошел, операция применяется к таблицам, если нет -
+    // отложенные под нее токены и пределы освобождаются
+    ResolveTransaction {
+        tx_id: u64,
+        transferred: bool,
+    },
+    // действие fungible-token, которое контракт ctoken рынка пересылает от имени `sender`;
+    // принимается только от ctokens, созданных этим контрактом. `Mint` и `Burn` недоступны -
+    // ctokens выпускаются при вкладе и сжигаются при выводе
+    CToken {
+        sender: ActorId,
+        action: FTAction,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum CompoundEvent {
+    TokensLended {
+        market: ActorId,
+        address: ActorId,
+        amount: Tokens,
+        ctokens_amount: CTokens,
+    },
+    TokensBorrowed {
+        market: ActorId,
+        address: ActorId,
+        amount: Tokens,
+        borrow_rate: Wad,
+    },
+    TokensRefunded {
+        market: ActorId,
+        address: ActorId,
+        amount: Tokens,
+    },
+    TokensWithdrawed {
+        market: ActorId,
+        address: ActorId,
+        amount: Tokens,
+    },
+    Liquidated {
+        liquidator: ActorId,
+        borrower: ActorId,
+        repay_market: ActorId,
+        collateral_market: ActorId,
+        repay_amount: Tokens,
+        ctokens_seized: CTokens,
+    },
+    MarketEntered {
+        market: ActorId,
+        address: ActorId,
+    },
+    MarketExited {
+        market: ActorId,
+        address: ActorId,
+    },
+    MarketAdded {
+        market: ActorId,
+    },
+    CollateralFactorSet {
+        market: ActorId,
+        collateral_factor: Wad,
+    },
+    RateModelSet {
+        market: ActorId,
+        rate_model: RateModel,
+    },
+    ReserveFactorSet {
+        market: ActorId,
+        reserve_factor: Wad,
+    },
+    SupplyCapSet {
+        market: ActorId,
+        supply_cap: Tokens,
+    },
+    BorrowCapSet {
+        market: ActorId,
+        borrow_cap: Tokens,
+    },
+    CloseFactorSet {
+        close_factor: Wad,
+    },
+    LiquidationIncentiveSet {
+        liquidation_incentive: Wad,
+    },
+    TimelockDelaySet {
+        delay: u64,
+    },
+    PauseGuardianSet {
+        guardian: ActorId,
+    },
+    ProposalQueued {
+        proposal_id: u64,
+        change: ParameterChange,
+        eta: u64,
+    },
+    ProposalCancelled {
+        proposal_id: u64,
+    },
+    AdminTransferProposed {
+        pending_admin: ActorId,
+    },
+    AdminTransferred {
+        old_admin: ActorId,
+        new_admin: ActorId,
+    },
+    PausedSet {
+        market: ActorId,
+        action: PausableAction,
+        paused: bool,
+    },
+    // `total_reserves` - резервы рынка после изменения
+    ReservesAdded {
+        market: ActorId,
+        amount: Tokens,
+        total_reserves: Tokens,
+    },
+    ReservesReduced {
+        market: ActorId,
+        amount: Tokens,
+        total_reserves: Tokens,
+    },
+    TransactionResolved {
+        tx_id: u64,
+        transferred: bool,
+    },
+    // ответ на `CompoundAction::CToken`, контракт ctoken отдает `event` отправителю
+    CToken {
+        market: ActorId,
+        event: FTEvent,
+    },
+}
+
+// ошибки действий: на каждое действие контракт отвечает `Result<CompoundEvent, CompoundError>`
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum CompoundError {
+    // сумма должна быть больше нуля
+    ZeroAmount,
+    // адрес не должен быть нулевым
+    ZeroAddress,
+    // у модели ставки излом вне допустимой загрузки или слишком высокая ставка
+    InvalidRateModel,
+    // залоговый коэффициент должен быть больше нуля и не больше `MAX_COLLATERAL_FACTOR`
+    InvalidCollateralFactor,
+    // доля резервов не должна превышать `MAX_RESERVE_FACTOR`
+    InvalidReserveFactor,
+    // `close_factor` должен быть больше нуля и не больше единицы
+    InvalidCloseFactor,
+    // бонус ликвидатора не должен превышать `MAX_LIQUIDATION_INCENTIVE`
+    InvalidLiquidationIncentive,
+    // задержка должна быть не меньше `MIN_TIMELOCK_DELAY` и не больше `MAX_TIMELOCK_DELAY`
+    InvalidTimelockDelay,
+    ProposalNotFound(u64),
+    // предложение уже отменено или применено
+    ProposalNotQueued(u64),
+    // задержка предложения еще не прошла
+    ProposalNotReady(u64),
+    // после `eta` прошло больше `GRACE_PERIOD`, предложение нужно поставить в очередь заново
+    ProposalExpired(u64),
+    MarketNotListed(ActorId),
+    MarketAlreadyListed(ActorId),
+    // у пользователя нет ни вклада, ни кредита в рынке
+    NoAssets(ActorId),
+    // в рынке не хватает свободных токенов
+    NotEnoughCash,
+    // сумма больше резервов рынка
+    NotEnoughReserves,
+    // вклад превысил бы предел вкладов рынка
+    SupplyCapExceeded(ActorId),
+    // кредит превысил бы предел кредитов рынка
+    BorrowCapExceeded(ActorId),
+    // сумма больше вклада или долга пользователя
+    AmountTooBig,
+    // перевод ctokens больше, чем разрешил владелец
+    NotEnoughAllowance,
+    // действие оставило бы долги пользователя без достаточного залога
+    InsufficientCollateral,
+    // ликвидировать можно только заемщика без достаточного залога
+    NotUndercollateralized(ActorId),
+    SelfLiquidation,
+    // за раз можно погасить не больше `close_factor` процентов долга
+    CloseFactorExceeded,
+    // у заемщика не хватает залога на погашенный долг с бонусом
+    NotEnoughCollateral,
+    // действие доступно только администратору
+    Unauthorized,
+    // действие в рынке приостановлено
+    ActionPaused {
+        market: ActorId,
+        action: PausableAction,
+    },
+    // по счету уже выполняется другое действие
+    AccountLocked(ActorId),
+    // нет свежей цены оракула и нет резервной цены
+    PriceUnavailable(ActorId),
+    // перевод токенов не прошел
+    TransferFailed,
+    TransactionNotFound(u64),
+    // перевод операции еще ждет ответа, улаживать можно только зависшую операцию
+    TransactionInProgress(u64),
+}
+
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+// пределы параметров риска, выйти за них не может и администратор
+pub const MAX_COLLATERAL_FACTOR: Wad = Wad::from_percent(90);
+pub const MAX_RESERVE_FACTOR: Wad = Wad::from_percent(50);
+pub const MAX_LIQUIDATION_INCENTIVE: Wad = Wad::from_percent(50);
+
+// наращивает индекс на годовую ставку `rate` за `elapsed` секунд, проценты округляются вверх
+pub fn accrue_index(index: Wad, rate: Wad, elapsed: u64) -> Wad {
+    let interest_factor = Wad(mul_div(
+        rate.0,
+        elapsed as u128,
+        SECONDS_PER_YEAR,
+        Rounding::Up,
+    ));
+    index + index.mul(interest_factor, Rounding::Up)
+}
+
+// курс ctoken: сколько токенов стоит один ctoken, округляется вниз;
+// пока ctokens не выпущены, действует начальный курс `ctoken_rate` ctokens за токен
+pub fn exchange_rate(
+    total_cash: Tokens,
+    total_borrows: Tokens,
+    total_reserves: Tokens,
+    total_ctokens: CTokens,
+    ctoken_rate: Wad,
+) -> Wad {
+    if total_ctokens.is_zero() {
+        return Wad::ONE.div(ctoken_rate, Rounding::Down);
+    }
+
+    let underlying = (total_cash + total_borrows).saturating_sub(total_reserves);
+    Wad::from_ratio(underlying.0, total_ctokens.0, Rounding::Down)
+}
+
+// токены рынка, которые еще можно вывести или занять: без тех, что уже переводятся из пула
+pub fn free_cash(total_cash: Tokens, outgoing_cash: Tokens) -> Tokens {
+    total_cash.saturating_sub(outgoing_cash)
+}
+
+// сколько еще можно занять до предела кредитов `borrow_cap` вместе с кредитами, чей перевод
+// еще не подтвержден; `None` - предела нет
+pub fn borrow_room(
+    borrow_cap: Tokens,
+    total_borrows: Tokens,
+    pending_borrows: Tokens,
+) -> Option<Tokens> {
+    (!borrow_cap.is_zero()).then(|| borrow_cap.saturating_sub(total_borrows + pending_borrows))
+}
+
+// вклад и кредит одного пользователя в одном рынке
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Assets {
+    pub lent_amount: CTokens,    // сколько ctokens получено за вклад
+    pub borrowed_amount: Tokens, // сколько занято на момент `borrow_index`
+    pub borrow_index: Wad,       // индекс кредита при последнем изменении кредита
+    pub is_collateral: bool,     // вклад учитывается как залог по кредитам во всех рынках
+}
+
+impl Assets {
+    // новый вклад сразу включается в залог
+    pub fn new(lent_amount: CTokens) -> Self {
+        Self {
+            lent_amount,
+            is_collateral: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_lend(&mut self, amount: CTokens) {
+        self.lent_amount += amount;
+    }
+
+    pub fn sub_lend(&mut self, amount: CTokens) {
+        self.lent_amount -= amount;
+    }
+
+    pub fn add_borrow(&mut self, amount: Tokens, borrow_index: Wad) {
+        self.borrowed_amount = self.get_borrow_amount(borrow_index) + amount;
+        self.borrow_index = borrow_index;
+    }
+
+    pub fn sub_borrow(&mut self, amount: Tokens, borrow_index: Wad) {
+        self.borrowed_amount = self.get_borrow_amount(borrow_index) - amount;
+        self.borrow_index = borrow_index;
+    }
+
+    // вклад в ctokens, проценты по нему начисляются через курс ctoken
+    pub fn get_lent_amount(&self) -> CTokens {
+        self.lent_amount
+    }
+
+    // сумма долга вместе с начисленными процентами при индексе `borrow_index`, округляется вверх
+    pub fn get_borrow_amount(&self, borrow_index: Wad) -> Tokens {
+        if self.borrow_index.is_zero() {
+            return self.borrowed_amount;
+        }
+        Tokens(mul_div(
+            self.borrowed_amount.0,
+            borrow_index.0,
+            self.borrow_index.0,
+            Rounding::Up,
+        ))
+    }
+}
+
+// типы сообщений контракта для Gear IDEA и других клиентов
+pub struct CompoundMetadata;
+
+impl Metadata for CompoundMetadata {
+    type Init = In<CompoundInit>;
+    type Handle = InOut<CompoundAction, Result<CompoundEvent, CompoundError>>;
+    type Others = ();
+    type Reply = ();
+    type Signal = ();
+    type State = InOut<StateQuery, StateReply>;
+}
+
+// запрос к `state()` контракта
+#[derive(Debug, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum StateQuery {
+    // все состояние контракта
+    State,
+    // сводка по всем рынкам
+    Markets,
+    // сводка по одному рынку
+    Market { market: ActorId },
+    // вклады и кредиты пользователя во всех рынках, где они есть
+    UserAssets { user: ActorId },
+    // запас по залогу пользователя, лимиты займа и вывода считаются в токенах `market`
+    AccountLiquidity { user: ActorId, market: ActorId },
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum StateReply {
+    State(Box<CompoundState>),
+    Markets(Vec<MarketSummary>),
+    // `None` - рынок не открыт
+    Market(Option<Box<MarketSummary>>),
+    // рынок и вклад с кредитом пользователя в нем
+    UserAssets(Vec<(ActorId, Assets)>),
+    // `None` - рынок не открыт
+    AccountLiquidity(Option<AccountLiquidity>),
+}
+
+// общие данные рынка без таблицы пользователей
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct MarketSummary {
+    pub market: ActorId,
+    pub ctoken: ActorId,
+    pub total_supply: Tokens, // все вклады вместе с процентами
+    pub total_cash: Tokens,
+    pub total_borrows: Tokens,
+    pub total_reserves: Tokens,
+    pub total_ctokens: CTokens,
+    pub utilization: Wad,
+    pub borrow_rate: Wad,
+    pub supply_rate: Wad,
+    pub exchange_rate: Wad,
+    pub price: Wad,
+    pub collateral_factor: Wad,
+    pub reserve_factor: Wad,
+    pub supply_cap: Tokens,
+    pub borrow_cap: Tokens,
+    pub paused_actions: Vec<PausableAction>,
+}
+
+// состояние контракта, которое отдается наружу через `state()`
+#[derive(Debug, Default, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct CompoundState {
+    pub admin: ActorId,
+    pub pending_admin: Option<ActorId>,
+    pub pause_guardian: ActorId,
+    pub close_factor: Wad,
+    pub liquidation_incentive: Wad,
+    pub oracle: ActorId,
+    pub max_price_age: u64,
+    pub timelock_delay: u64,
+    pub proposals: Vec<(u64, Proposal)>,
+    pub init_time: u64,
+    pub markets: Vec<(ActorId, MarketState)>,
+    pub ctoken_code: CodeId,
+    pub transactions: Vec<(u64, Transaction)>, // операции, чей перевод еще не подтвержден
+    pub locked_accounts: Vec<ActorId>,
+}
+
+// состояние одного рынка
+#[derive(Debug, Default, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct MarketState {
+    pub token_address: ActorId,
+    pub ctoken: ActorId, // контракт ctoken рынка с интерфейсом fungible-token
+    pub collateral_factor: Wad,
+    pub rate_model: RateModel,
+    pub reserve_factor: Wad,
+    pub supply_cap: Tokens,
+    pub borrow_cap: Tokens,
+    pub paused_actions: Vec<PausableAction>,
+    pub borrow_rate: Wad,
+    pub supply_rate: Wad,
+    pub ctoken_rate: Wad,
+    pub fallback_price: Wad,
+    pub price: Wad,
+    pub price_time: u64,
+    pub user_assets: Vec<(ActorId, Assets)>,
+    pub allowances: Vec<(ActorId, ActorId, CTokens)>, // владелец, кому разрешено и сколько ctokens
+    pub borrow_index: Wad,
+    pub accrual_time: u64,
+    pub total_cash: Tokens,
+    pub outgoing_cash: Tokens, // часть `total_cash`, которая уже переводится из пула
+    pub incoming_supply: Tokens, // вклады, чей перевод в пул еще не подтвержден
+    pub pending_borrows: Tokens, // кредиты, чей перевод еще не подтвержден
+    pub total_borrows: Tokens,
+    pub total_reserves: Tokens,
+    pub total_ctokens: CTokens,
+    pub exchange_rate: Wad,
+}
@@ -0,0 +1,145 @@
+// запас по залогу пользователя: сколько он занял и сколько еще может занять или вывести,
+// одинаково считается в действиях контракта и в запросах к состоянию
+
+use crate::{Assets, Rounding, Tokens, Wad};
+use gstd::prelude::*;
+
+// позиция пользователя в одном рынке, суммы в токенах рынка
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct MarketPosition {
+    pub collateral_amount: Tokens, // стоимость ctokens пользователя по текущему курсу
+    pub borrow_amount: Tokens,     // долг вместе с начисленными процентами
+    pub price: Wad,                // цена токена рынка в общей единице
+    pub collateral_factor: Wad,    // залоговый коэффициент рынка
+    pub is_collateral: bool,       // вклад включен в залог
+}
+
+impl MarketPosition {
+    // `assets` - вклад и кредит пользователя в рынке, `None` - в рынке у него ничего нет
+    pub fn new(
+        assets: Option<&Assets>,
+        exchange_rate: Wad,
+        borrow_index: Wad,
+        price: Wad,
+        collateral_factor: Wad,
+    ) -> Self {
+        let Some(assets) = assets else {
+            return Self {
+                price,
+                collateral_factor,
+                ..Default::default()
+            };
+        };
+        Self {
+            collateral_amount: assets
+                .get_lent_amount()
+                .to_tokens(exchange_rate, Rounding::Down),
+            borrow_amount: assets.get_borrow_amount(borrow_index),
+            price,
+            collateral_factor,
+            is_collateral: assets.is_collateral,
+        }
+    }
+
+    // стоимость вклада в общей единице, округляется вниз
+    pub fn collateral_value(&self) -> u128 {
+        self.price.scale(self.collateral_amount.0, Rounding::Down)
+    }
+
+    // стоимость долга в общей единице, округляется вверх
+    pub fn borrow_value(&self) -> u128 {
+        self.price.scale(self.borrow_amount.0, Rounding::Up)
+    }
+
+    // сколько можно занять под этот вклад, в общей единице
+    pub fn borrow_limit(&self) -> u128 {
+        if !self.is_collateral {
+            return 0;
+        }
+        self.collateral_factor
+            .scale(self.collateral_value(), Rounding::Down)
+    }
+
+    // сколько токенов рынка стоят `value` в общей единице, без цены - ни одного
+    fn to_tokens(&self, value: u128) -> Tokens {
+        if self.price.is_zero() {
+            return Tokens::default();
+        }
+        Tokens(self.price.unscale(value, Rounding::Down))
+    }
+}
+
+// суммы по всем рынкам пользователя в общей единице, лимиты займа и вывода - в токенах выбранного рынка
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct AccountLiquidity {
+    pub collateral_value: u128,      // стоимость вкладов, включенных в залог
+    pub borrow_value: u128,          // стоимость долгов вместе с начисленными процентами
+    pub borrow_limit: u128,          // на какую сумму всего можно занять под этот залог
+    pub available_to_borrow: Tokens, // сколько еще токенов выбранного рынка можно занять
+    pub max_withdraw: Tokens, // сколько можно вывести из выбранного рынка, не нарушив предел залога
+    pub health_factor: Wad,   // borrow_limit / borrow_value, меньше единицы - можно ликвидировать
+}
+
+impl AccountLiquidity {
+    // `positions` - позиции пользователя во всех рынках, `market` - позиция в рынке,
+    // для которого считается `max_withdraw`
+    pub fn new(positions: &[MarketPosition], market: &MarketPosition) -> Self {
+        let collateral_value = positions
+            .iter()
+            .filter(|position| position.is_collateral)
+            .map(MarketPosition::collateral_value)
+            .sum();
+        let borrow_value: u128 = positions.iter().map(MarketPosition::borrow_value).sum();
+        let borrow_limit: u128 = positions.iter().map(MarketPosition::borrow_limit).sum();
+        let available_value = borrow_limit.saturating_sub(borrow_value);
+
+        let available_to_borrow = market.to_tokens(available_value);
+        // без долгов вклад можно вывести целиком, не теряя на округлении предела
+        let max_withdraw =
+            if borrow_value != 0 && market.is_collateral && !market.collateral_factor.is_zero() {
+                // вывод `x` токенов уменьшает предел залога на `x * collateral_factor`
+                let withdraw_value = market
+                    .collateral_factor
+                    .unscale(available_value, Rounding::Down);
+                market
+                    .collateral_amount
+                    .min(market.to_tokens(withdraw_value))
+            } else {
+                market.collateral_amount
+            };
+        let health_factor = if borrow_value == 0 {
+            Wad::MAX
+        } else {
+            Wad::checked_from_ratio(borrow_limit, borrow_value, Rounding::Down).unwrap_or(Wad::MAX)
+        };
+
+        Self {
+            collateral_value,
+            borrow_value,
+            borrow_limit,
+            available_to_borrow,
+            max_withdraw,
+            health_factor,
+        }
+    }
+
+    // лимиты с учетом самого рынка: вывести и занять можно не больше его свободных токенов,
+    // а занять - еще и не больше остатка до предела кредитов, `None` - предела нет
+    pub fn limit_by_market(mut self, free_cash: Tokens, borrow_room: Option<Tokens>) -> Self {
+        self.max_withdraw = self.max_withdraw.min(free_cash);
+        self.available_to_borrow = self.available_to_borrow.min(free_cash);
+        if let Some(borrow_room) = borrow_room {
+            self.available_to_borrow = self.available_to_borrow.min(borrow_room);
+        }
+        self
+    }
+
+    // долг превысил предел залога
+    pub fn is_undercollateralized(&self) -> bool {
+        self.borrow_value > self.borrow_limit
+    }
+}
@@ -0,0 +1,88 @@
+// операции пула: проверенное действие пользователя и перевод токенов, после которого оно
+// применяется к таблицам контракта. Пока перевод не подтвержден, операция лежит в журнале:
+// если сообщение прервется, она остается там до решения администратора
+
+use crate::{CTokens, Tokens};
+use gstd::{prelude::*, ActorId};
+
+// один перевод токенов
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Transfer {
+    pub token: ActorId,
+    pub from: ActorId,
+    pub to: ActorId,
+    pub amount: u128,
+}
+
+// изменения в таблицах контракта, которые применяются после перевода
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum Operation {
+    Lend {
+        market: ActorId,
+        amount: Tokens, // ctokens считаются при применении, по курсу на момент перевода
+    },
+    Withdraw {
+        market: ActorId,
+        amount: Tokens,
+        ctokens_amount: CTokens,
+    },
+    Borrow {
+        market: ActorId,
+        amount: Tokens,
+    },
+    Refund {
+        market: ActorId,
+        amount: Tokens,
+    },
+    Liquidate {
+        borrower: ActorId,
+        repay_market: ActorId,
+        collateral_market: ActorId,
+        repay_amount: Tokens,
+        ctokens_seized: CTokens,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum TransactionStatus {
+    // перевод отправлен, ждем ответа
+    InProgress,
+    // сообщение прервалось после отправки перевода, прошел ли он - неизвестно;
+    // счета операции заблокированы до `CompoundAction::ResolveTransaction`
+    Stuck,
+}
+
+// операция, чей перевод еще не подтвержден
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Transaction {
+    pub user: ActorId,        // кто начал операцию
+    pub operation: Operation, // что изменить в таблицах после перевода
+    pub transfer: Transfer,   // перевод операции
+    pub locked: Vec<ActorId>, // счета, которые операция держит заблокированными кроме `user`
+    pub status: TransactionStatus,
+}
+
+impl Transaction {
+    pub fn new(
+        user: ActorId,
+        operation: Operation,
+        transfer: Transfer,
+        locked: Vec<ActorId>,
+    ) -> Self {
+        Self {
+            user,
+            operation,
+            transfer,
+            locked,
+            status: TransactionStatus::InProgress,
+        }
+    }
+}
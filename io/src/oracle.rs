@@ -0,0 +1,32 @@
+// интерфейс оракула цен: контракт запрашивает у него цену токена сообщением,
+// чтобы сравнивать залог и долги в разных токенах в одной общей единице;
+// цена - стоимость одного токена в общей единице
+
+use crate::Wad;
+use gstd::{prelude::*, ActorId};
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum OracleAction {
+    // узнать последнюю цену токена
+    GetPrice { token: ActorId },
+    // обновить цену токена (только владелец оракула)
+    SetPrice { token: ActorId, price: Wad },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum OracleEvent {
+    // `updated_at` - время обновления цены в секундах, для неизвестного токена цена и время нулевые
+    Price {
+        token: ActorId,
+        price: Wad,
+        updated_at: u64,
+    },
+    PriceSet {
+        token: ActorId,
+        price: Wad,
+    },
+}
@@ -0,0 +1,115 @@
+// модели процентных ставок: ставка по кредитам зависит от загрузки пула,
+// все ставки годовые (`Wad::ONE` = 100% годовых)
+
+use crate::{Rounding, Wad};
+use gstd::prelude::*;
+
+pub const MAX_BORROW_RATE: Wad = Wad::from_percent(1000); // предел ставки по кредитам при полной загрузке
+
+pub trait InterestRateModel {
+    // годовая ставка по кредитам при текущей загрузке пула
+    fn borrow_rate(&self, cash: u128, borrows: u128, reserves: u128) -> Wad;
+
+    // годовая ставка по вкладам: проценты заемщиков делятся на все вложенные токены
+    fn supply_rate(&self, cash: u128, borrows: u128, reserves: u128) -> Wad {
+        self.borrow_rate(cash, borrows, reserves)
+            .mul(utilization(cash, borrows, reserves), Rounding::Down)
+    }
+}
+
+// доля занятых токенов от всех токенов пула
+pub fn utilization(cash: u128, borrows: u128, reserves: u128) -> Wad {
+    if borrows == 0 {
+        return Wad::ZERO;
+    }
+
+    let total = (cash + borrows).saturating_sub(reserves);
+    if total == 0 {
+        return Wad::ONE;
+    }
+    Wad::from_ratio(borrows, total, Rounding::Down).min(Wad::ONE)
+}
+
+// ставка растет линейно с загрузкой: base_rate + multiplier * utilization
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct LinearRateModel {
+    pub base_rate: Wad,
+    pub multiplier: Wad,
+}
+
+impl InterestRateModel for LinearRateModel {
+    fn borrow_rate(&self, cash: u128, borrows: u128, reserves: u128) -> Wad {
+        let utilization = utilization(cash, borrows, reserves);
+        self.base_rate + utilization.mul(self.multiplier, Rounding::Up)
+    }
+}
+
+// ставка с изломом: до загрузки `kink` растет как линейная модель,
+// после излома каждый процент загрузки дорожает на `jump_multiplier`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct JumpRateModel {
+    pub base_rate: Wad,
+    pub multiplier: Wad,
+    pub kink: Wad,
+    pub jump_multiplier: Wad,
+}
+
+impl InterestRateModel for JumpRateModel {
+    fn borrow_rate(&self, cash: u128, borrows: u128, reserves: u128) -> Wad {
+        let utilization = utilization(cash, borrows, reserves);
+        let normal_utilization = utilization.min(self.kink);
+        let excess_utilization = utilization - normal_utilization;
+
+        self.base_rate
+            + normal_utilization.mul(self.multiplier, Rounding::Up)
+            + excess_utilization.mul(self.jump_multiplier, Rounding::Up)
+    }
+}
+
+// модель, выбираемая при инициализации контракта
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum RateModel {
+    Linear(LinearRateModel),
+    JumpRate(JumpRateModel),
+}
+
+impl Default for RateModel {
+    fn default() -> Self {
+        Self::Linear(Default::default())
+    }
+}
+
+impl RateModel {
+    // излом должен лежать внутри допустимой загрузки, а ставка даже при полной загрузке
+    // не должна превышать `MAX_BORROW_RATE`
+    pub fn is_valid(&self) -> bool {
+        let rates_bounded = |rates: &[Wad]| rates.iter().all(|rate| *rate <= MAX_BORROW_RATE);
+        match self {
+            Self::Linear(model) => {
+                rates_bounded(&[model.base_rate, model.multiplier])
+                    && self.borrow_rate(0, 1, 0) <= MAX_BORROW_RATE
+            }
+            Self::JumpRate(model) => {
+                !model.kink.is_zero()
+                    && model.kink <= Wad::ONE
+                    && rates_bounded(&[model.base_rate, model.multiplier, model.jump_multiplier])
+                    && self.borrow_rate(0, 1, 0) <= MAX_BORROW_RATE
+            }
+        }
+    }
+}
+
+impl InterestRateModel for RateModel {
+    fn borrow_rate(&self, cash: u128, borrows: u128, reserves: u128) -> Wad {
+        match self {
+            Self::Linear(model) => model.borrow_rate(cash, borrows, reserves),
+            Self::JumpRate(model) => model.borrow_rate(cash, borrows, reserves),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// отложенные изменения параметров риска: администратор ставит изменение в очередь,
+// и применить его можно только после задержки, чтобы вкладчики успели на него отреагировать
+
+use crate::{MarketConfig, RateModel, Tokens, Wad};
+use gstd::{prelude::*, ActorId};
+
+pub const MIN_TIMELOCK_DELAY: u64 = 2 * 24 * 60 * 60; // меньше задержки вкладчики не успеют отреагировать
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60; // предел задержки в секундах
+pub const GRACE_PERIOD: u64 = 14 * 24 * 60 * 60; // сколько секунд после `eta` изменение еще можно применить
+
+// изменение параметров, которое проходит через очередь
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum ParameterChange {
+    // новый рынок сразу становится залогом для кредитов во всех рынках, поэтому тоже ждет задержки
+    AddMarket(MarketConfig),
+    CollateralFactor {
+        market: ActorId,
+        collateral_factor: Wad,
+    },
+    RateModel {
+        market: ActorId,
+        rate_model: RateModel,
+    },
+    ReserveFactor {
+        market: ActorId,
+        reserve_factor: Wad,
+    },
+    // 0 - без предела
+    SupplyCap {
+        market: ActorId,
+        supply_cap: Tokens,
+    },
+    BorrowCap {
+        market: ActorId,
+        borrow_cap: Tokens,
+    },
+    CloseFactor {
+        close_factor: Wad,
+    },
+    LiquidationIncentive {
+        liquidation_incentive: Wad,
+    },
+    TimelockDelay {
+        delay: u64,
+    },
+    // нулевой адрес - хранителя паузы нет
+    PauseGuardian {
+        guardian: ActorId,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum ProposalStatus {
+    Queued,
+    // `at` - время в секундах
+    Cancelled { at: u64 },
+    Executed { at: u64 },
+}
+
+// предложение остается в истории и после отмены или применения
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Proposal {
+    pub change: ParameterChange,
+    pub queued_at: u64, // когда поставлено в очередь
+    pub eta: u64,       // раньше этого времени применить нельзя
+    pub status: ProposalStatus,
+}
@@ -0,0 +1,87 @@
+// единицы сумм контракта:
+// - `Tokens` - токены рынка (вклады по текущему курсу, кредиты, свободные токены пула, резервы);
+// - `CTokens` - ctokens, которые выдаются за вклад и дорожают вместе с курсом ctoken.
+// Перейти от одной единицы к другой можно только через курс ctoken (`to_ctokens`/`to_tokens`),
+// поэтому сложить, вычесть или сравнить токены с ctokens не получится - это ошибка компиляции
+
+use crate::{Rounding, Wad};
+use core::{
+    iter::Sum,
+    ops::{Add, AddAssign, Sub, SubAssign},
+};
+use gstd::prelude::*;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Tokens(pub u128);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Decode, Encode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct CTokens(pub u128);
+
+impl Tokens {
+    // сколько ctokens стоят эти токены по курсу `exchange_rate`
+    pub fn to_ctokens(self, exchange_rate: Wad, rounding: Rounding) -> CTokens {
+        CTokens(exchange_rate.unscale(self.0, rounding))
+    }
+}
+
+impl CTokens {
+    // сколько токенов стоят эти ctokens по курсу `exchange_rate`
+    pub fn to_tokens(self, exchange_rate: Wad, rounding: Rounding) -> Tokens {
+        Tokens(exchange_rate.scale(self.0, rounding))
+    }
+}
+
+macro_rules! impl_amount {
+    ($amount:ident) => {
+        impl $amount {
+            pub fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+
+            pub fn saturating_sub(self, other: Self) -> Self {
+                Self(self.0.saturating_sub(other.0))
+            }
+        }
+
+        impl Add for $amount {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self(self.0.checked_add(other.0).expect("Amount overflow"))
+            }
+        }
+
+        impl Sub for $amount {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self(self.0.checked_sub(other.0).expect("Amount underflow"))
+            }
+        }
+
+        impl AddAssign for $amount {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $amount {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl Sum for $amount {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::default(), Add::add)
+            }
+        }
+    };
+}
+
+impl_amount!(Tokens);
+impl_amount!(CTokens);
@@ -0,0 +1,112 @@
+use compound_io::{decimal::WAD, *};
+
+// 7 * B = 2 * u128::MAX + 1: частное ровно u128::MAX и остаток 1
+const B: u128 = 97_223_533_405_982_418_132_392_744_980_505_203_273;
+
+#[test]
+fn mul_div_rounds_remainder() {
+    assert_eq!(checked_mul_div(10, 3, 4, Rounding::Down), Some(7));
+    assert_eq!(checked_mul_div(10, 3, 4, Rounding::Up), Some(8));
+    // без остатка округление вверх ничего не добавляет
+    assert_eq!(checked_mul_div(12, 3, 4, Rounding::Up), Some(9));
+    assert_eq!(checked_mul_div(0, u128::MAX, 1, Rounding::Up), Some(0));
+}
+
+#[test]
+fn mul_div_with_wide_product() {
+    // произведение не влезает в u128, а результат влезает
+    assert_eq!(
+        checked_mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Up),
+        Some(u128::MAX)
+    );
+    assert_eq!(
+        checked_mul_div(u128::MAX, 2, 4, Rounding::Down),
+        Some(u128::MAX / 2)
+    );
+    assert_eq!(
+        checked_mul_div(u128::MAX, 2, 4, Rounding::Up),
+        Some(u128::MAX / 2 + 1)
+    );
+    assert_eq!(
+        checked_mul_div(u128::MAX, WAD, WAD, Rounding::Down),
+        Some(u128::MAX)
+    );
+    assert_eq!(checked_mul_div(7, B, 2, Rounding::Down), Some(u128::MAX));
+}
+
+#[test]
+fn mul_div_overflow() {
+    assert_eq!(checked_mul_div(u128::MAX, 2, 1, Rounding::Down), None);
+    assert_eq!(
+        checked_mul_div(u128::MAX, u128::MAX, 2, Rounding::Down),
+        None
+    );
+    // частное влезает, но округление вверх выводит его за предел
+    assert_eq!(checked_mul_div(7, B, 2, Rounding::Up), None);
+    assert_eq!(Wad::MAX.checked_mul(Wad(2 * WAD), Rounding::Down), None);
+    assert_eq!(Wad::MAX.checked_div(Wad(WAD / 2), Rounding::Down), None);
+    assert_eq!(Wad::checked_from_ratio(u128::MAX, 1, Rounding::Down), None);
+}
+
+#[test]
+#[should_panic(expected = "Multiplication overflow")]
+fn mul_div_panics_on_overflow() {
+    mul_div(u128::MAX, 2, 1, Rounding::Down);
+}
+
+#[test]
+#[should_panic(expected = "Division by zero")]
+fn mul_div_by_zero() {
+    checked_mul_div(1, 1, 0, Rounding::Down);
+}
+
+#[test]
+fn wad_mul_div() {
+    let half = Wad::from_percent(50);
+    assert_eq!(half.mul(half, Rounding::Down), Wad::from_percent(25));
+    assert_eq!(
+        half.div(Wad::from_percent(25), Rounding::Down),
+        Wad(2 * WAD)
+    );
+    // меньше минимальной доли: вниз - ноль, вверх - одна доля
+    assert_eq!(Wad(1).mul(Wad(1), Rounding::Down), Wad::ZERO);
+    assert_eq!(Wad(1).mul(Wad(1), Rounding::Up), Wad(1));
+    assert_eq!(
+        Wad::ONE.div(Wad(3 * WAD), Rounding::Down),
+        Wad(333_333_333_333_333_333)
+    );
+    assert_eq!(
+        Wad::ONE.div(Wad(3 * WAD), Rounding::Up),
+        Wad(333_333_333_333_333_334)
+    );
+    assert_eq!(
+        Wad::from_ratio(1, 3, Rounding::Up),
+        Wad(333_333_333_333_333_334)
+    );
+    assert_eq!(Wad::from_ratio(1, 4, Rounding::Up), Wad::from_percent(25));
+}
+
+#[test]
+#[should_panic(expected = "Multiplication overflow")]
+fn wad_mul_panics_on_overflow() {
+    Wad::MAX.mul(Wad(2 * WAD), Rounding::Down);
+}
+
+#[test]
+fn wad_scale_unscale() {
+    let half = Wad::from_percent(50);
+    assert_eq!(half.scale(3, Rounding::Down), 1);
+    assert_eq!(half.scale(3, Rounding::Up), 2);
+    assert_eq!(half.scale(4, Rounding::Up), 2);
+    assert_eq!(half.unscale(3, Rounding::Down), 6);
+
+    let two = Wad(2 * WAD);
+    assert_eq!(two.unscale(5, Rounding::Down), 2);
+    assert_eq!(two.unscale(5, Rounding::Up), 3);
+    assert_eq!(two.unscale(4, Rounding::Up), 2);
+
+    // большие суммы считаются через 256-битное произведение
+    assert_eq!(two.scale(u128::MAX / 2, Rounding::Down), u128::MAX - 1);
+    assert_eq!(Wad::ONE.unscale(u128::MAX, Rounding::Down), u128::MAX);
+    assert_eq!(Wad(3 * WAD).unscale(u128::MAX, Rounding::Up), u128::MAX / 3);
+}
@@ -0,0 +1,75 @@
+use compound_io::{rate_model::utilization, *};
+
+fn jump_model() -> JumpRateModel {
+    JumpRateModel {
+        base_rate: Wad::from_percent(2),
+        multiplier: Wad::from_percent(10),
+        kink: Wad::from_percent(80),
+        jump_multiplier: Wad::from_percent(200),
+    }
+}
+
+#[test]
+fn utilization_excludes_reserves() {
+    assert_eq!(utilization(100, 0, 0), Wad::ZERO);
+    assert_eq!(utilization(60, 40, 0), Wad::from_percent(40));
+    // резервы не принадлежат вкладчикам: занято 60 из 80 токенов
+    assert_eq!(utilization(30, 60, 10), Wad::from_percent(75));
+    // резервы больше свободных токенов: загрузка не выше полной
+    assert_eq!(utilization(0, 10, 5), Wad::ONE);
+    assert_eq!(utilization(10, 10, 20), Wad::ONE);
+}
+
+#[test]
+fn jump_rate_below_kink() {
+    // 2% + 40% * 10%
+    assert_eq!(jump_model().borrow_rate(60, 40, 0), Wad::from_percent(6));
+    assert_eq!(jump_model().borrow_rate(100, 0, 0), Wad::from_percent(2));
+}
+
+#[test]
+fn jump_rate_at_kink() {
+    // 2% + 80% * 10%, излом еще не дорожает
+    assert_eq!(jump_model().borrow_rate(20, 80, 0), Wad::from_percent(10));
+}
+
+#[test]
+fn jump_rate_above_kink() {
+    // 2% + 80% * 10% + 10% * 200%
+    assert_eq!(jump_model().borrow_rate(10, 90, 0), Wad::from_percent(30));
+    // с резервами загрузка та же: занято 90 из 100 токенов вкладчиков
+    assert_eq!(jump_model().borrow_rate(30, 90, 20), Wad::from_percent(30));
+    // 2% + 80% * 10% + 20% * 200%
+    assert_eq!(jump_model().borrow_rate(0, 90, 0), Wad::from_percent(50));
+}
+
+#[test]
+fn jump_rate_validation() {
+    assert!(RateModel::JumpRate(jump_model()).is_valid());
+    assert!(!RateModel::JumpRate(JumpRateModel {
+        kink: Wad::ZERO,
+        ..jump_model()
+    })
+    .is_valid());
+    assert!(!RateModel::JumpRate(JumpRateModel {
+        kink: Wad::from_percent(101),
+        ..jump_model()
+    })
+    .is_valid());
+
+    // каждая ставка в пределе, но при полной загрузке 500% + 200% + 350% выше него
+    let steep = JumpRateModel {
+        base_rate: Wad::from_percent(500),
+        multiplier: Wad::from_percent(400),
+        kink: Wad::from_percent(50),
+        jump_multiplier: Wad::from_percent(700),
+    };
+    assert!(!RateModel::JumpRate(steep.clone()).is_valid());
+    // 500% + 200% + 300% ровно на пределе
+    assert!(RateModel::JumpRate(JumpRateModel {
+        jump_multiplier: Wad::from_percent(600),
+        ..steep
+    })
+    .is_valid());
+    assert_eq!(MAX_BORROW_RATE, Wad::from_percent(1000));
+}
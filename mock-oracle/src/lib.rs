@@ -0,0 +1,65 @@
+#![no_std]
+
+// простой оракул цен для тестов: владелец сам выставляет цены токенов,
+// контракт compound запрашивает их сообщением `OracleAction::GetPrice`
+
+use compound_io::{
+    oracle::{OracleAction, OracleEvent},
+    Wad,
+};
+use gstd::{collections::BTreeMap, exec, msg, prelude::*, ActorId};
+
+// собранный контракт для тестов на gtest
+#[cfg(not(target_arch = "wasm32"))]
+include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+
+#[derive(Default)]
+struct MockOracle {
+    owner: ActorId,                        // кто может менять цены
+    prices: BTreeMap<ActorId, (Wad, u64)>, // цена токена и время ее обновления
+}
+
+static mut ORACLE: Option<MockOracle> = None;
+
+impl MockOracle {
+    fn get_price(&self, token: ActorId) {
+        let (price, updated_at) = self.prices.get(&token).copied().unwrap_or_default();
+        msg::reply(
+            OracleEvent::Price {
+                token,
+                price,
+                updated_at,
+            },
+            0,
+        )
+        .expect("Error in reply");
+    }
+
+    fn set_price(&mut self, token: ActorId, price: Wad) {
+        assert_eq!(msg::source(), self.owner, "Only owner can set prices");
+        self.prices
+            .insert(token, (price, exec::block_timestamp() / 1000));
+        msg::reply(OracleEvent::PriceSet { token, price }, 0).expect("Error in reply");
+    }
+}
+
+#[no_mangle]
+extern "C" fn handle() {
+    let action: OracleAction = msg::load().expect("Unable to decode OracleAction");
+    let oracle = unsafe { static_mut!(ORACLE).get_or_insert(Default::default()) };
+
+    match action {
+        OracleAction::GetPrice { token } => oracle.get_price(token),
+        OracleAction::SetPrice { token, price } => oracle.set_price(token, price),
+    }
+}
+
+#[no_mangle]
+extern "C" fn init() {
+    unsafe {
+        ORACLE = Some(MockOracle {
+            owner: msg::source(),
+            ..Default::default()
+        })
+    };
+}
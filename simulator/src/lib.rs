@@ -0,0 +1,18 @@
+// экономический симулятор пула: тот же учет, что и в контракте (`compound-core`), прогоняется
+// на хосте по пути цены залога с поведением вкладчиков, заемщиков и ликвидатора. Нужен, чтобы
+// до развертывания проверить залоговый коэффициент, модель ставки и бонус ликвидатора
+
+pub use report::{DailyStats, Report};
+pub use scenario::{Crash, PricePath, Scenario};
+pub use simulation::Simulation;
+
+mod report;
+mod rng;
+mod scenario;
+mod simulation;
+
+// сколько единиц в одном токене: суммы в учете целые, поэтому токены дробятся, как в ERC-20
+pub const UNIT: u128 = 1_000_000;
+// шаг симуляции - час
+pub const STEP_SECONDS: u64 = 60 * 60;
+pub const STEPS_PER_DAY: u64 = 24;
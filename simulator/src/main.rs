@@ -0,0 +1,168 @@
+// запуск симулятора из командной строки: параметры задаются флагами `--name value`,
+// проценты - числами вроде `75` или `2.5`
+
+use compound_io::{decimal::WAD, JumpRateModel, LinearRateModel, RateModel, Wad};
+use compound_simulator::{Crash, DailyStats, PricePath, Scenario, Simulation};
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: compound-simulator [options]
+
+risk parameters, in percent:
+  --collateral-factor <pct>      collateral factor of the volatile token (75)
+  --liquidation-incentive <pct>  liquidator bonus (8)
+  --close-factor <pct>           share of debt repaid per liquidation (50)
+  --reserve-factor <pct>         share of interest kept as reserves (10)
+  --base-rate <pct>              borrow rate at zero utilization (2)
+  --multiplier <pct>             borrow rate growth per utilization (20)
+  --kink <pct>                   utilization of the jump rate model kink
+  --jump-multiplier <pct>        borrow rate growth above the kink
+
+price path of the volatile token, starting at 1:
+  --volatility <pct>             annual volatility (80)
+  --drift <pct>                  annual drift (0)
+  --crash <pct>@<day>[:<hours>]  price drop spread over hours (24)
+  --prices <file>                hourly prices, one per line, instead of a random path
+
+users and run:
+  --lenders <n>                  stable token lenders (20)
+  --borrowers <n>                borrowers against the volatile token (50)
+  --target-usage <pct>           share of the borrow limit borrowers aim for (80)
+  --reaction <pct>               hourly chance a careful borrower repays near the limit (20)
+  --slippage <pct>               liquidator loss on selling collateral (3)
+  --days <n>                     simulated days (365)
+  --seed <n>                     random seed (1)
+  --csv <file>                   write daily market stats";
+
+fn main() {
+    let (scenario, csv) = parse_args().unwrap_or_else(|error| {
+        eprintln!("{error}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    let simulation = Simulation::new(scenario).unwrap_or_else(|error| {
+        eprintln!("invalid parameters: {error:?}");
+        process::exit(2);
+    });
+    let report = simulation.run();
+    println!("{report}");
+
+    if let Some(path) = csv {
+        let lines: Vec<_> = std::iter::once(DailyStats::CSV_HEADER.to_string())
+            .chain(report.daily.iter().map(DailyStats::to_csv))
+            .collect();
+        if let Err(error) = fs::write(&path, lines.join("\n") + "\n") {
+            eprintln!("unable to write {path}: {error}");
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_args() -> Result<(Scenario, Option<String>), String> {
+    let mut scenario = Scenario::default();
+    let mut csv = None;
+    let (mut volatility, mut drift, mut crash) = (0.8, 0.0, None);
+    let (mut base_rate, mut multiplier) = (Wad::from_percent(2), Wad::from_percent(20));
+    let (mut kink, mut jump_multiplier) = (None, None);
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{USAGE}");
+            process::exit(0);
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {flag}"))?;
+        match flag.as_str() {
+            "--collateral-factor" => scenario.collateral_factor = wad(&value)?,
+            "--liquidation-incentive" => scenario.liquidation_incentive = wad(&value)?,
+            "--close-factor" => scenario.close_factor = wad(&value)?,
+            "--reserve-factor" => scenario.reserve_factor = wad(&value)?,
+            "--base-rate" => base_rate = wad(&value)?,
+            "--multiplier" => multiplier = wad(&value)?,
+            "--kink" => kink = Some(wad(&value)?),
+            "--jump-multiplier" => jump_multiplier = Some(wad(&value)?),
+            "--volatility" => volatility = percent(&value)?,
+            "--drift" => drift = percent(&value)?,
+            "--crash" => crash = Some(parse_crash(&value)?),
+            "--prices" => scenario.prices = PricePath::Replay(read_prices(&value)?),
+            "--lenders" => scenario.lenders = number(&value)?,
+            "--borrowers" => scenario.borrowers = number(&value)?,
+            "--target-usage" => scenario.target_usage = percent(&value)?,
+            "--reaction" => scenario.reaction = percent(&value)?,
+            "--slippage" => scenario.slippage = percent(&value)?,
+            "--days" => scenario.days = number(&value)?,
+            "--seed" => scenario.seed = number(&value)?,
+            "--csv" => csv = Some(value),
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+
+    scenario.rate_model = match kink {
+        Some(kink) => RateModel::JumpRate(JumpRateModel {
+            base_rate,
+            multiplier,
+            kink,
+            jump_multiplier: jump_multiplier.unwrap_or(multiplier),
+        }),
+        None => RateModel::Linear(LinearRateModel {
+            base_rate,
+            multiplier,
+        }),
+    };
+    if !matches!(scenario.prices, PricePath::Replay(_)) {
+        scenario.prices = PricePath::Random {
+            volatility,
+            drift,
+            crash,
+        };
+    }
+    Ok((scenario, csv))
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid number {value}"))
+}
+
+fn percent(value: &str) -> Result<f64, String> {
+    let percent: f64 = number(value)?;
+    if !percent.is_finite() || percent < 0.0 {
+        return Err(format!("invalid percent {value}"));
+    }
+    Ok(percent / 100.0)
+}
+
+fn wad(value: &str) -> Result<Wad, String> {
+    Ok(Wad((percent(value)? * WAD as f64).round() as u128))
+}
+
+// `30@100` - обвал на 30% в сотый день за сутки, `30@100:1` - за час
+fn parse_crash(value: &str) -> Result<Crash, String> {
+    let (drop, when) = value
+        .split_once('@')
+        .ok_or_else(|| format!("invalid crash {value}"))?;
+    let (day, hours) = when.split_once(':').unwrap_or((when, "24"));
+    let drop = percent(drop)?;
+    if drop >= 1.0 {
+        return Err(format!("invalid crash {value}"));
+    }
+    Ok(Crash {
+        day: number(day)?,
+        drop,
+        hours: number(hours)?,
+    })
+}
+
+fn read_prices(path: &str) -> Result<Vec<f64>, String> {
+    let contents = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let prices = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(number)
+        .collect::<Result<Vec<f64>, _>>()?;
+    if prices.iter().any(|price| *price <= 0.0) {
+        return Err(format!("{path}: prices must be positive"));
+    }
+    Ok(prices)
+}
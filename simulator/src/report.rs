@@ -0,0 +1,92 @@
+// итоги прогона: непокрытый долг, загрузка и доходность рынка стабильного токена;
+// суммы в стабильных токенах, ставки и доли - в долях единицы
+
+use std::fmt;
+
+#[derive(Debug, Default, Clone)]
+pub struct Report {
+    pub days: u64,
+    pub bad_debt: f64,            // непокрытый долг в конце прогона
+    pub max_bad_debt: f64,        // наибольший непокрытый долг за прогон
+    pub underwater_accounts: u32, // заемщики, которых в конце прогона можно ликвидировать
+    pub avg_utilization: f64,
+    pub max_utilization: f64,
+    pub avg_borrow_rate: f64,
+    pub lender_apy: f64, // годовая доходность вкладчика по росту курса ctoken
+    pub reserves: f64,   // накопленные резервы
+    pub liquidations: u32,
+    pub repaid: f64, // долг, погашенный ликвидатором
+    pub min_price: f64,
+    pub final_price: f64,
+    pub daily: Vec<DailyStats>,
+}
+
+// снимок рынка стабильного токена в конце дня
+#[derive(Debug, Default, Clone)]
+pub struct DailyStats {
+    pub day: u64,
+    pub price: f64, // цена залога
+    pub utilization: f64,
+    pub borrow_rate: f64,
+    pub supply_rate: f64,
+    pub exchange_rate: f64,
+    pub total_borrows: f64,
+    pub total_reserves: f64,
+    pub bad_debt: f64,
+    pub liquidations: u32, // ликвидаций с начала прогона
+}
+
+impl DailyStats {
+    pub const CSV_HEADER: &'static str = "day,price,utilization,borrow_rate,supply_rate,exchange_rate,total_borrows,total_reserves,bad_debt,liquidations";
+
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{:.6},{:.6},{:.6},{:.6},{:.9},{:.2},{:.2},{:.2},{}",
+            self.day,
+            self.price,
+            self.utilization,
+            self.borrow_rate,
+            self.supply_rate,
+            self.exchange_rate,
+            self.total_borrows,
+            self.total_reserves,
+            self.bad_debt,
+            self.liquidations,
+        )
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "days:                {}", self.days)?;
+        writeln!(
+            f,
+            "collateral price:    final {:.4}, min {:.4}",
+            self.final_price, self.min_price
+        )?;
+        writeln!(
+            f,
+            "bad debt:            final {:.2}, max {:.2}",
+            self.bad_debt, self.max_bad_debt
+        )?;
+        writeln!(f, "underwater accounts: {}", self.underwater_accounts)?;
+        writeln!(
+            f,
+            "utilization:         avg {:.2}%, max {:.2}%",
+            self.avg_utilization * 100.0,
+            self.max_utilization * 100.0
+        )?;
+        writeln!(
+            f,
+            "borrow rate:         avg {:.2}%",
+            self.avg_borrow_rate * 100.0
+        )?;
+        writeln!(f, "lender APY:          {:.2}%", self.lender_apy * 100.0)?;
+        writeln!(f, "reserves:            {:.2}", self.reserves)?;
+        write!(
+            f,
+            "liquidations:        {}, repaid {:.2}",
+            self.liquidations, self.repaid
+        )
+    }
+}
@@ -0,0 +1,102 @@
+// параметры прогона: риск-параметры рынков, путь цены залога и поведение пользователей
+
+use crate::{rng::Rng, STEPS_PER_DAY, STEP_SECONDS};
+use compound_io::{LinearRateModel, RateModel, Wad};
+
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub days: u64,
+    pub seed: u64,
+    pub collateral_factor: Wad, // залоговый коэффициент волатильного токена
+    pub liquidation_incentive: Wad, // бонус ликвидатора
+    pub close_factor: Wad,      // доля долга, которую можно погасить за одну ликвидацию
+    pub reserve_factor: Wad,    // доля процентов в резервы рынка стабильного токена
+    pub rate_model: RateModel,  // модель ставки рынка стабильного токена
+    pub prices: PricePath,      // цена волатильного токена в стабильных
+    pub lenders: u32,
+    pub borrowers: u32,
+    pub target_usage: f64, // до какой доли предела займа занимают заемщики
+    pub reaction: f64, // вероятность за час, что осторожный заемщик погасит часть долга при малом запасе
+    pub slippage: f64, // потери ликвидатора на продаже залога, при бонусе меньше них он не ликвидирует
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            days: 365,
+            seed: 1,
+            collateral_factor: Wad::from_percent(75),
+            liquidation_incentive: Wad::from_percent(8),
+            close_factor: Wad::from_percent(50),
+            reserve_factor: Wad::from_percent(10),
+            rate_model: RateModel::Linear(LinearRateModel {
+                base_rate: Wad::from_percent(2),
+                multiplier: Wad::from_percent(20),
+            }),
+            prices: PricePath::Random {
+                volatility: 0.8,
+                drift: 0.0,
+                crash: None,
+            },
+            lenders: 20,
+            borrowers: 50,
+            target_usage: 0.8,
+            reaction: 0.2,
+            slippage: 0.03,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PricePath {
+    // геометрическое броуновское движение с годовыми волатильностью и сносом
+    Random {
+        volatility: f64,
+        drift: f64,
+        crash: Option<Crash>,
+    },
+    // готовые цены по часам, например исторические
+    Replay(Vec<f64>),
+}
+
+// обвал цены на долю `drop` в день `day`, растянутый на `hours` часов
+#[derive(Debug, Clone, Copy)]
+pub struct Crash {
+    pub day: u64,
+    pub drop: f64,
+    pub hours: u64,
+}
+
+impl PricePath {
+    // цены на каждый шаг, начиная с единицы; готовый путь короче прогона продолжается последней ценой
+    pub fn generate(&self, steps: u64, rng: &mut Rng) -> Vec<f64> {
+        match self {
+            Self::Random {
+                volatility,
+                drift,
+                crash,
+            } => {
+                let dt = STEP_SECONDS as f64 / (365.0 * 24.0 * 60.0 * 60.0);
+                let mut price = 1.0;
+                let mut prices = Vec::with_capacity(steps as usize);
+                for step in 0..steps {
+                    prices.push(price);
+                    price *= ((drift - volatility * volatility / 2.0) * dt
+                        + volatility * dt.sqrt() * rng.normal())
+                    .exp();
+                    if let Some(crash) = crash {
+                        let start = crash.day * STEPS_PER_DAY;
+                        let hours = crash.hours.max(1);
+                        if (start..start + hours).contains(&step) {
+                            price *= (1.0 - crash.drop).powf(1.0 / hours as f64);
+                        }
+                    }
+                }
+                prices
+            }
+            Self::Replay(path) => (0..steps as usize)
+                .map(|step| path.get(step).or(path.last()).copied().unwrap_or(1.0))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+// прогон по часам: начисление процентов и новая цена, действия пользователей, ликвидации
+// и снимок показателей. Все действия проходят через те же проверки `Pool`, что и в контракте,
+// переводы токенов считаются успешными
+
+use crate::{rng::Rng, DailyStats, Report, Scenario, STEPS_PER_DAY, STEP_SECONDS, UNIT};
+use compound_core::Pool;
+use compound_io::decimal::WAD;
+use compound_io::*;
+use gstd::ActorId;
+
+const COLLATERAL: u64 = 10; // волатильный токен, который заемщики вкладывают в залог
+const STABLE: u64 = 20; // стабильный токен, который вкладывают вкладчики и занимают заемщики
+const LIQUIDATOR: u64 = 1;
+const LENDERS: u64 = 1_000; // id первого вкладчика
+const BORROWERS: u64 = 100_000; // id первого заемщика
+
+// вероятность за день, что вкладчик пополнит или частично выведет вклад
+const LENDER_ACTIVITY: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy)]
+enum Behaviour {
+    Passive, // занимает один раз и ничего не делает
+    Careful, // гасит часть долга, когда запас по залогу почти исчерпан
+    Greedy,  // занимает еще, когда залог дорожает
+}
+
+struct Borrower {
+    id: ActorId,
+    behaviour: Behaviour,
+    target_usage: f64, // до какой доли предела займа занимает
+}
+
+pub struct Simulation {
+    scenario: Scenario,
+    pool: Pool,
+    rng: Rng,
+    prices: Vec<f64>,
+    lenders: Vec<ActorId>,
+    borrowers: Vec<Borrower>,
+    report: Report,
+}
+
+impl Simulation {
+    pub fn new(scenario: Scenario) -> Result<Self, CompoundError> {
+        let mut pool = Pool::new(scenario.close_factor, scenario.liquidation_incentive)?;
+        for (token, collateral_factor) in [
+            (COLLATERAL, scenario.collateral_factor),
+            (STABLE, MAX_COLLATERAL_FACTOR),
+        ] {
+            pool.list_market(
+                MarketConfig {
+                    token_address: token.into(),
+                    collateral_factor,
+                    rate_model: scenario.rate_model.clone(),
+                    reserve_factor: scenario.reserve_factor,
+                    ctoken_rate: Wad::ONE,
+                    fallback_price: Wad::ONE,
+                    ..Default::default()
+                },
+                0,
+            )?;
+        }
+
+        let mut rng = Rng::new(scenario.seed);
+        let prices = scenario
+            .prices
+            .generate(scenario.days * STEPS_PER_DAY + 1, &mut rng);
+        let lenders = (0..scenario.lenders as u64)
+            .map(|i| (LENDERS + i).into())
+            .collect();
+        let borrowers = (0..scenario.borrowers as u64)
+            .map(|i| Borrower {
+                id: (BORROWERS + i).into(),
+                behaviour: match rng.next_u64() % 3 {
+                    0 => Behaviour::Passive,
+                    1 => Behaviour::Careful,
+                    _ => Behaviour::Greedy,
+                },
+                target_usage: scenario.target_usage * rng.range(0.5, 1.0),
+            })
+            .collect();
+
+        Ok(Self {
+            scenario,
+            pool,
+            rng,
+            prices,
+            lenders,
+            borrowers,
+            report: Report::default(),
+        })
+    }
+
+    pub fn run(mut self) -> Report {
+        let start_rate = self.setup();
+        let steps = self.prices.len() as u64;
+        let (mut utilization_sum, mut borrow_rate_sum) = (0.0, 0.0);
+
+        for step in 1..steps {
+            self.tick(step);
+            self.lenders_act();
+            self.borrowers_act();
+            self.liquidate();
+
+            let stable = self.stable();
+            let utilization = to_f64(rate_model::utilization(
+                stable.total_cash.0,
+                stable.total_borrows.0,
+                stable.total_reserves.0,
+            ));
+            utilization_sum += utilization;
+            borrow_rate_sum += to_f64(stable.borrow_rate());
+            self.report.max_utilization = self.report.max_utilization.max(utilization);
+            let bad_debt = self.bad_debt();
+            self.report.max_bad_debt = self.report.max_bad_debt.max(bad_debt);
+
+            if step % STEPS_PER_DAY == 0 {
+                self.report.daily.push(self.daily_stats(step, bad_debt));
+            }
+        }
+
+        let days = self.scenario.days as f64;
+        let rate_growth = to_f64(self.stable().exchange_rate()) / start_rate;
+        let samples = steps.saturating_sub(1).max(1) as f64;
+        self.report.days = self.scenario.days;
+        self.report.avg_utilization = utilization_sum / samples;
+        self.report.avg_borrow_rate = borrow_rate_sum / samples;
+        self.report.lender_apy = rate_growth.powf(365.0 / days) - 1.0;
+        self.report.reserves = tokens(self.stable().total_reserves.0);
+        self.report.bad_debt = self.bad_debt();
+        self.report.underwater_accounts = self
+            .borrowers
+            .iter()
+            .filter(|borrower| self.liquidity(&borrower.id).is_undercollateralized())
+            .count() as u32;
+        self.report.final_price = self.prices.last().copied().unwrap_or(1.0);
+        self.report.min_price = self.prices.iter().copied().fold(f64::MAX, f64::min);
+        self.report
+    }
+
+    // вкладчики вносят стабильные токены, заемщики вносят залог и занимают до своей доли предела;
+    // возвращает начальный курс ctoken стабильного рынка
+    fn setup(&mut self) -> f64 {
+        self.tick(0);
+        for i in 0..self.lenders.len() {
+            let amount = self.rng.range(10_000.0, 100_000.0);
+            self.execute(self.lenders[i], |pool| {
+                pool.lend(STABLE.into(), units(amount))
+            });
+        }
+        for i in 0..self.borrowers.len() {
+            let amount = self.rng.range(1_000.0, 20_000.0);
+            let id = self.borrowers[i].id;
+            self.execute(id, |pool| pool.lend(COLLATERAL.into(), units(amount)));
+            self.borrow_to_target(i);
+        }
+        to_f64(self.stable().exchange_rate())
+    }
+
+    fn tick(&mut self, step: u64) {
+        let now = step * STEP_SECONDS;
+        let price = Wad((self.prices[step as usize] * WAD as f64) as u128).max(Wad(1));
+        self.pool.accrue_interest(now);
+        self.pool
+            .set_price(&COLLATERAL.into(), price, now)
+            .expect("Collateral market is listed");
+    }
+
+    fn lenders_act(&mut self) {
+        let probability = LENDER_ACTIVITY / STEPS_PER_DAY as f64;
+        for i in 0..self.lenders.len() {
+            let id = self.lenders[i];
+            if self.rng.chance(probability) {
+                let amount = self.rng.range(1_000.0, 20_000.0);
+                self.execute(id, |pool| pool.lend(STABLE.into(), units(amount)));
+            }
+            if self.rng.chance(probability) {
+                // выводит часть вклада, насколько хватает свободных токенов
+                let share = self.rng.range(0.1, 0.5);
+                let stable = self.stable();
+                let lent = stable
+                    .user_assets
+                    .get(&id)
+                    .map(|assets| stable.count_tokens(assets.get_lent_amount()).0)
+                    .unwrap_or_default();
+                let amount = ((lent as f64 * share) as u128).min(stable.total_cash.0);
+                if amount > 0 {
+                    self.execute(id, |pool| pool.withdraw(id, STABLE.into(), Tokens(amount)));
+                }
+            }
+        }
+    }
+
+    fn borrowers_act(&mut self) {
+        for i in 0..self.borrowers.len() {
+            let Borrower {
+                id,
+                behaviour,
+                target_usage,
+            } = self.borrowers[i];
+            let liquidity = self.liquidity(&id);
+            let (limit, value) = (liquidity.borrow_limit as f64, liquidity.borrow_value as f64);
+            match behaviour {
+                Behaviour::Careful
+                    if value > limit * 0.95 && self.rng.chance(self.scenario.reaction) =>
+                {
+                    // гасит долг до своей доли предела
+                    let debt = self.stable().position(&id).borrow_amount.0;
+                    let amount = ((value - limit * target_usage) as u128).min(debt);
+                    if amount > 0 {
+                        self.execute(id, |pool| pool.refund(id, STABLE.into(), Tokens(amount)));
+                    }
+                }
+                Behaviour::Greedy if value < limit * target_usage * 0.9 => self.borrow_to_target(i),
+                _ => {}
+            }
+        }
+    }
+
+    fn borrow_to_target(&mut self, i: usize) {
+        let Borrower {
+            id, target_usage, ..
+        } = self.borrowers[i];
+        let liquidity = self.liquidity(&id);
+        let target = liquidity.borrow_limit as f64 * target_usage;
+        let amount = ((target - liquidity.borrow_value as f64).max(0.0) as u128)
+            .min(liquidity.available_to_borrow.0)
+            .min(self.stable().total_cash.0);
+        if amount > 0 {
+            self.execute(id, |pool| pool.borrow(id, STABLE.into(), Tokens(amount)));
+        }
+    }
+
+    // ликвидатор гасит долги заемщиков без достаточного залога, если бонус покрывает его потери
+    // на продаже залога, и сразу выводит полученный залог
+    fn liquidate(&mut self) {
+        if to_f64(self.scenario.liquidation_incentive) <= self.scenario.slippage {
+            return;
+        }
+        let liquidator = ActorId::from(LIQUIDATOR);
+        for i in 0..self.borrowers.len() {
+            let id = self.borrowers[i].id;
+            // за шаг несколько ликвидаций подряд, пока заемщик не вернется в норму
+            for _ in 0..5 {
+                if !self.liquidity(&id).is_undercollateralized() {
+                    break;
+                }
+                let Some(repay_amount) = self.repay_amount(&id) else {
+                    break;
+                };
+                let Some(CompoundEvent::Liquidated { ctokens_seized, .. }) =
+                    self.execute(liquidator, |pool| {
+                        pool.liquidate(
+                            liquidator,
+                            id,
+                            STABLE.into(),
+                            COLLATERAL.into(),
+                            repay_amount,
+                        )
+                    })
+                else {
+                    break;
+                };
+                self.report.liquidations += 1;
+                self.report.repaid += tokens(repay_amount.0);
+
+                let seized = self.collateral().count_tokens(ctokens_seized);
+                self.execute(liquidator, |pool| {
+                    pool.withdraw(liquidator, COLLATERAL.into(), seized)
+                });
+            }
+        }
+    }
+
+    // сколько можно погасить за одну ликвидацию: долю `close_factor` долга,
+    // но не больше, чем покрывает оставшийся залог с бонусом
+    fn repay_amount(&self, borrower: &ActorId) -> Option<Tokens> {
+        let debt = self.stable().position(borrower).borrow_amount.0;
+        let by_close_factor = self.pool.close_factor.scale(debt, Rounding::Down);
+
+        let collateral = self.collateral();
+        let collateral_value = collateral.price.scale(
+            collateral.position(borrower).collateral_amount.0,
+            Rounding::Down,
+        );
+        let repay_value =
+            (Wad::ONE + self.pool.liquidation_incentive).unscale(collateral_value, Rounding::Down);
+        let by_collateral = self
+            .stable()
+            .price
+            .unscale(repay_value, Rounding::Down)
+            .saturating_sub(1);
+
+        let amount = by_close_factor.min(by_collateral);
+        (amount > 0).then_some(Tokens(amount))
+    }
+
+    // проверяет действие и сразу применяет его, переводы токенов в симуляции всегда проходят
+    fn execute(
+        &mut self,
+        user: ActorId,
+        check: impl FnOnce(&Pool) -> Result<Operation, CompoundError>,
+    ) -> Option<CompoundEvent> {
+        let operation = check(&self.pool).ok()?;
+        self.pool.reserve(&operation).ok()?;
+        self.pool.apply_operation(user, operation).ok()
+    }
+
+    // непокрытый долг: насколько долги заемщиков дороже всего их залога
+    fn bad_debt(&self) -> f64 {
+        let bad_debt: u128 = self
+            .borrowers
+            .iter()
+            .map(|borrower| {
+                let liquidity = self.liquidity(&borrower.id);
+                liquidity
+                    .borrow_value
+                    .saturating_sub(liquidity.collateral_value)
+            })
+            .sum();
+        tokens(bad_debt)
+    }
+
+    fn daily_stats(&self, step: u64, bad_debt: f64) -> DailyStats {
+        let stable = self.stable();
+        DailyStats {
+            day: step / STEPS_PER_DAY,
+            price: self.prices[step as usize],
+            utilization: to_f64(rate_model::utilization(
+                stable.total_cash.0,
+                stable.total_borrows.0,
+                stable.total_reserves.0,
+            )),
+            borrow_rate: to_f64(stable.borrow_rate()),
+            supply_rate: to_f64(stable.supply_rate()),
+            exchange_rate: to_f64(stable.exchange_rate()),
+            total_borrows: tokens(stable.total_borrows.0),
+            total_reserves: tokens(stable.total_reserves.0),
+            bad_debt,
+            liquidations: self.report.liquidations,
+        }
+    }
+
+    fn liquidity(&self, user: &ActorId) -> AccountLiquidity {
+        self.pool
+            .account_liquidity(user, &STABLE.into())
+            .expect("Stable market is listed")
+    }
+
+    fn stable(&self) -> &compound_core::Market {
+        self.pool
+            .market(&STABLE.into())
+            .expect("Stable market is listed")
+    }
+
+    fn collateral(&self) -> &compound_core::Market {
+        self.pool
+            .market(&COLLATERAL.into())
+            .expect("Collateral market is listed")
+    }
+}
+
+fn to_f64(value: Wad) -> f64 {
+    value.0 as f64 / WAD as f64
+}
+
+fn units(amount: f64) -> Tokens {
+    Tokens((amount * UNIT as f64) as u128)
+}
+
+fn tokens(amount: u128) -> f64 {
+    amount as f64 / UNIT as f64
+}
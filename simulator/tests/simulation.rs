@@ -0,0 +1,92 @@
+use compound_io::Wad;
+use compound_simulator::{Crash, PricePath, Scenario, Simulation};
+
+fn run(scenario: Scenario) -> compound_simulator::Report {
+    Simulation::new(scenario).expect("valid scenario").run()
+}
+
+#[test]
+fn calm_market_has_no_bad_debt() {
+    let report = run(Scenario {
+        days: 60,
+        prices: PricePath::Random {
+            volatility: 0.2,
+            drift: 0.0,
+            crash: None,
+        },
+        ..Default::default()
+    });
+
+    assert_eq!(report.bad_debt, 0.0);
+    assert_eq!(report.underwater_accounts, 0);
+    assert!(report.avg_utilization > 0.0);
+    assert!(report.lender_apy > 0.0);
+    assert!(report.reserves > 0.0);
+    assert_eq!(report.daily.len(), 60);
+    assert_eq!(report.daily.last().map(|stats| stats.day), Some(60));
+}
+
+#[test]
+fn liquidations_cover_crash() {
+    let report = run(Scenario {
+        days: 30,
+        prices: PricePath::Random {
+            volatility: 0.2,
+            drift: 0.0,
+            crash: Some(Crash {
+                day: 10,
+                drop: 0.3,
+                hours: 24,
+            }),
+        },
+        ..Default::default()
+    });
+
+    assert!(report.liquidations > 0);
+    assert!(report.repaid > 0.0);
+    assert_eq!(report.bad_debt, 0.0);
+}
+
+// бонус меньше потерь на продаже залога - ликвидатор бездействует, и обвал оставляет непокрытый долг
+#[test]
+fn unprofitable_liquidations_leave_bad_debt() {
+    let report = run(Scenario {
+        days: 30,
+        liquidation_incentive: Wad::from_percent(2),
+        slippage: 0.05,
+        prices: PricePath::Replay(
+            (0..30 * 24)
+                .map(|hour| if hour < 10 * 24 { 1.0 } else { 0.5 })
+                .collect(),
+        ),
+        ..Default::default()
+    });
+
+    assert_eq!(report.liquidations, 0);
+    assert!(report.bad_debt > 0.0);
+    assert!(report.underwater_accounts > 0);
+    assert_eq!(report.min_price, 0.5);
+}
+
+#[test]
+fn same_seed_same_report() {
+    let scenario = Scenario {
+        days: 10,
+        ..Default::default()
+    };
+    let first = run(scenario.clone());
+    let second = run(scenario);
+
+    assert_eq!(first.final_price, second.final_price);
+    assert_eq!(first.bad_debt, second.bad_debt);
+    assert_eq!(first.lender_apy, second.lender_apy);
+}
+
+#[test]
+fn invalid_parameters() {
+    let scenario = Scenario {
+        collateral_factor: Wad::from_percent(100),
+        ..Default::default()
+    };
+    assert!(Simulation::new(scenario).is_err());
+}
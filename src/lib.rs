@@ -0,0 +1,107 @@
+#![no_std]
+
+// примерная реализация смарт-контракта, заменяющего банковские операции на основе акторной модели;
+// весь учет и проверки делает автомат `compound_core::Compound`, а контракт только передает ему
+// сообщения и выполняет его эффекты: запросы цен у оракула, переводы токенов и создание ctokens
+
+use compound_core::{Compound, Step};
+use compound_io::*;
+use gstd::{critical, exec, msg, prelude::*, ActorId};
+use utils::{create_ctoken, make_transfer};
+
+mod oracle;
+mod storage;
+mod utils;
+
+// время блока в секундах
+fn now() -> u64 {
+    exec::block_timestamp() / 1000
+}
+
+// выполняем шаги действия, пока автомат не вернет итог
+async fn execute(action: CompoundAction, caller: ActorId) -> Result<CompoundEvent, CompoundError> {
+    let mut step = storage::with(|compound| compound.apply(action, caller, now()))?;
+    loop {
+        step = match step {
+            Step::Done(event) => return Ok(event),
+            Step::FetchPrices {
+                oracle,
+                tokens,
+                action,
+                caller,
+            } => {
+                let mut prices = Vec::with_capacity(tokens.len());
+                for token in tokens {
+                    prices.push((token, oracle::fetch(oracle, token).await));
+                }
+                storage::with(|compound| compound.prices_fetched(action, caller, prices, now()))?
+            }
+            Step::Transfer { transfer, pending } => {
+                let result = make_transfer(&transfer).await;
+                storage::with(|compound| compound.transferred(pending, result, now()))?
+            }
+            Step::CreateCToken { code, market } => {
+                let ctoken = create_ctoken(code, market);
+                storage::with(|compound| compound.ctoken_created(market, ctoken))?
+            }
+        };
+    }
+}
+
+#[gstd::async_main]
+async fn main() {
+    let action: CompoundAction = msg::load().expect("Unable to decode CompoundAction");
+    let msg_source = msg::source(); // из сообщения получаем действие, которое нужно совершить
+    let (accounts, locked) = storage::with(|compound| {
+        let accounts = compound.action_accounts(&action, msg_source);
+        let locked = compound.lock_accounts(&accounts);
+        (accounts, locked)
+    });
+
+    let result = match locked {
+        // пока действие не завершится, другие действия с этими счетами отклоняются
+        Ok(()) => {
+            // если действие упадет после ожидания ответа, изменения до ожидания уже сохранены,
+            // поэтому блокировку снимает хук, который выполняется при ошибке; операция, чей
+            // перевод уже отправлен, остается в журнале зависшей
+            let hook_accounts = accounts.clone();
+            critical::set_hook(move || {
+                storage::try_with(|compound| compound.abort_action(&hook_accounts));
+            });
+            let result = execute(action, msg_source).await;
+            storage::with(|compound| compound.unlock_accounts(&accounts));
+            let _ = critical::take_hook();
+            result
+        }
+        Err(error) => Err(error),
+    };
+    msg::reply(result, 0).expect("Error in reply");
+}
+
+#[no_mangle]
+extern "C" fn init() {
+    //инициализация нового контракта
+    let config: CompoundInit = msg::load().expect("Unable to decode CompoundInit");
+
+    // проверяем, что переданные данные корректны
+    let mut compound = Compound::new(config, msg::source(), exec::program_id(), now())
+        .unwrap_or_else(|error| panic!("Invalid init config: {:?}", error));
+    // рынки из конфигурации открыты сразу, создаем их ctokens
+    let markets: Vec<_> = compound.pool.markets.keys().copied().collect();
+    for market in markets {
+        let ctoken = create_ctoken(compound.ctoken_code, market);
+        compound
+            .ctoken_created(market, ctoken)
+            .expect("Market is listed");
+    }
+
+    storage::init(compound); //создаем контракт с переданными данными
+}
+
+#[no_mangle]
+extern "C" fn state() {
+    // отдаем текущее состояние контракта для чтения; изменения в state() не сохраняются
+    let query: StateQuery = msg::load().expect("Unable to decode StateQuery");
+    let reply = storage::with(|compound| compound.query(query, now()));
+    msg::reply(reply, 0).expect("Failed to share state");
+}
@@ -0,0 +1,22 @@
+// запрос цены у контракта оракула, выбор между ней и резервной ценой делает `compound_core`
+
+use compound_io::{
+    oracle::{OracleAction, OracleEvent},
+    Wad,
+};
+use gstd::{msg, ActorId};
+
+// цена и время ее обновления, `None` - оракул не ответил
+pub async fn fetch(oracle: ActorId, token: ActorId) -> Option<(Wad, u64)> {
+    let reply =
+        msg::send_for_reply_as::<_, OracleEvent>(oracle, OracleAction::GetPrice { token }, 0, 0)
+            .ok()?
+            .await
+            .ok()?;
+    match reply {
+        OracleEvent::Price {
+            price, updated_at, ..
+        } => Some((price, updated_at)),
+        _ => None,
+    }
+}
@@ -0,0 +1,27 @@
+// состояние контракта между сообщениями: создается один раз в `init`, а обработчики сообщений
+// и хук блокировок получают его только через этот модуль, единственное место с `unsafe`
+
+use compound_core::Compound;
+use core::cell::RefCell;
+
+struct Storage(RefCell<Option<Compound>>);
+
+// программа Gear выполняется в одном потоке: пока одно сообщение не дошло до ожидания ответа
+// или не завершилось, другое сообщение к состоянию не обращается
+unsafe impl Sync for Storage {}
+
+static STORAGE: Storage = Storage(RefCell::new(None));
+
+pub fn init(compound: Compound) {
+    *STORAGE.0.borrow_mut() = Some(compound);
+}
+
+// доступ к состоянию только внутри `f`: ссылка не переживает вызов и не держится через
+// ожидание ответа, а вложенный вызов падает на `RefCell`, а не дает две ссылки сразу
+pub fn try_with<R>(f: impl FnOnce(&mut Compound) -> R) -> Option<R> {
+    STORAGE.0.borrow_mut().as_mut().map(f)
+}
+
+pub fn with<R>(f: impl FnOnce(&mut Compound) -> R) -> R {
+    try_with(f).expect("Contract is not initialized")
+}
@@ -0,0 +1,36 @@
+// вспомогательные функции: переводы токенов и создание контрактов ctokens
+
+use compound_io::{
+    ft::{FTAction, FTEvent},
+    CompoundError, Transfer,
+};
+use gstd::{msg, prog, ActorId, CodeId};
+
+pub async fn transfer_tokens(
+    token_address: ActorId,
+    from: ActorId,
+    to: ActorId,
+    amount: u128,
+) -> Result<(), CompoundError> {
+    msg::send_for_reply_as::<_, FTEvent>(
+        token_address,
+        FTAction::Transfer { from, to, amount },
+        0,
+        0,
+    )
+    .map_err(|_| CompoundError::TransferFailed)?
+    .await
+    .map_err(|_| CompoundError::TransferFailed)?;
+    Ok(())
+}
+
+pub async fn make_transfer(transfer: &Transfer) -> Result<(), CompoundError> {
+    transfer_tokens(transfer.token, transfer.from, transfer.to, transfer.amount).await
+}
+
+// создает контракт ctoken рынка; рынок служит солью, поэтому у каждого рынка свой адрес ctoken
+pub fn create_ctoken(code: CodeId, market: ActorId) -> ActorId {
+    let (_, ctoken) = prog::create_program_bytes(code, market, [], 0)
+        .unwrap_or_else(|error| panic!("Unable to create ctoken: {:?}", error));
+    ctoken
+}
@@ -0,0 +1,92 @@
+#![no_std]
+
+// запросы только для чтения поверх состояния, которое возвращает `state()` контракта
+
+use compound_io::{
+    borrow_room, free_cash, AccountLiquidity, Assets, CTokens, CompoundState, MarketPosition,
+    MarketState, PausableAction, Rounding, Tokens,
+};
+use gstd::{prelude::*, ActorId};
+
+pub fn market<'a>(state: &'a CompoundState, market: &ActorId) -> Option<&'a MarketState> {
+    state
+        .markets
+        .iter()
+        .find_map(|(id, market_state)| (id == market).then_some(market_state))
+}
+
+pub fn user_assets<'a>(
+    state: &'a CompoundState,
+    market_id: &ActorId,
+    user: &ActorId,
+) -> Option<&'a Assets> {
+    market(state, market_id)?
+        .user_assets
+        .iter()
+        .find_map(|(id, assets)| (id == user).then_some(assets))
+}
+
+// вклад пользователя в ctokens
+pub fn ctokens_amount(state: &CompoundState, market_id: &ActorId, user: &ActorId) -> CTokens {
+    user_assets(state, market_id, user).map_or_else(Default::default, Assets::get_lent_amount)
+}
+
+// вклад пользователя в токенах по текущему курсу ctoken
+pub fn lent_amount(state: &CompoundState, market_id: &ActorId, user: &ActorId) -> Tokens {
+    market(state, market_id).map_or_else(Default::default, |market_state| {
+        ctokens_amount(state, market_id, user).to_tokens(market_state.exchange_rate, Rounding::Down)
+    })
+}
+
+pub fn borrow_amount(state: &CompoundState, market_id: &ActorId, user: &ActorId) -> Tokens {
+    market(state, market_id).map_or_else(Default::default, |market_state| {
+        user_assets(state, market_id, user).map_or_else(Default::default, |assets| {
+            assets.get_borrow_amount(market_state.borrow_index)
+        })
+    })
+}
+
+// запас по залогу пользователя во всех рынках, посчитанный так же, как его проверяют действия
+// контракта; `None` - рынок не открыт
+pub fn account_liquidity(
+    state: &CompoundState,
+    user: &ActorId,
+    market_id: &ActorId,
+) -> Option<AccountLiquidity> {
+    let market_state = market(state, market_id)?;
+    let positions: Vec<_> = state
+        .markets
+        .iter()
+        .map(|(id, _)| position(state, id, user))
+        .collect();
+    Some(
+        AccountLiquidity::new(&positions, &position(state, market_id, user)).limit_by_market(
+            free_cash(market_state.total_cash, market_state.outgoing_cash),
+            borrow_room(
+                market_state.borrow_cap,
+                market_state.total_borrows,
+                market_state.pending_borrows,
+            ),
+        ),
+    )
+}
+
+fn position(state: &CompoundState, market_id: &ActorId, user: &ActorId) -> MarketPosition {
+    let Some(market_state) = market(state, market_id) else {
+        return Default::default();
+    };
+
+    MarketPosition::new(
+        user_assets(state, market_id, user),
+        market_state.exchange_rate,
+        market_state.borrow_index,
+        market_state.price,
+        market_state.collateral_factor,
+    )
+}
+
+// приостановлено ли действие в рынке
+pub fn is_paused(state: &CompoundState, market_id: &ActorId, action: PausableAction) -> bool {
+    market(state, market_id)
+        .is_some_and(|market_state| market_state.paused_actions.contains(&action))
+}
@@ -0,0 +1,433 @@
+use compound_io::*;
+use gtest::{Program, System};
+use utils::*;
+
+mod utils;
+
+#[test]
+fn add_market_through_timelock() {
+    let sys = System::new();
+    init(&sys);
+
+    let change = ParameterChange::AddMarket(market_config(20));
+    assert_eq!(
+        send(
+            &sys,
+            LENDER,
+            CompoundAction::QueueProposal {
+                change: change.clone()
+            }
+        ),
+        Err(CompoundError::Unauthorized)
+    );
+    let Ok(CompoundEvent::ProposalQueued { proposal_id, .. }) = send(
+        &sys,
+        ADMIN,
+        CompoundAction::QueueProposal {
+            change: change.clone(),
+        },
+    ) else {
+        panic!("Unable to queue proposal");
+    };
+    // до конца задержки рынка нет, и под его залог ничего не занять
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id }),
+        Err(CompoundError::ProposalNotReady(proposal_id))
+    );
+    assert!(compound_state::market(&state(&sys), &20.into()).is_none());
+
+    skip_seconds(&sys, TIMELOCK_DELAY);
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id }),
+        Ok(CompoundEvent::MarketAdded { market: 20.into() })
+    );
+    // у нового рынка сразу есть свой контракт ctoken
+    assert_eq!(
+        send_ctoken(&sys, ADMIN, 20, ft::FTAction::TotalSupply),
+        Ok(ft::FTEvent::TotalSupply(0))
+    );
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::QueueProposal { change }),
+        Err(CompoundError::MarketAlreadyListed(20.into()))
+    );
+
+    let invalid = MarketConfig {
+        collateral_factor: Wad::from_percent(95),
+        ..market_config(22)
+    };
+    assert_eq!(
+        send(
+            &sys,
+            ADMIN,
+            CompoundAction::QueueProposal {
+                change: ParameterChange::AddMarket(invalid)
+            }
+        ),
+        Err(CompoundError::InvalidCollateralFactor)
+    );
+}
+
+#[test]
+fn init_rejects_short_timelock() {
+    let sys = System::new();
+    sys.init_logger();
+
+    let compound = Program::current_with_id(&sys, COMPOUND);
+    let message_id = compound.send(
+        ADMIN,
+        CompoundInit {
+            timelock_delay: TIMELOCK_DELAY - 1,
+            ..init_config()
+        },
+    );
+    assert!(sys.run_next_block().failed.contains(&message_id));
+}
+
+#[test]
+fn proposal_waits_for_delay() {
+    let sys = System::new();
+    init(&sys);
+
+    let change = ParameterChange::CollateralFactor {
+        market: TOKEN_A.into(),
+        collateral_factor: Wad::from_percent(70),
+    };
+    assert_eq!(
+        send(
+            &sys,
+            LENDER,
+            CompoundAction::QueueProposal {
+                change: change.clone()
+            }
+        ),
+        Err(CompoundError::Unauthorized)
+    );
+    let Ok(CompoundEvent::ProposalQueued { proposal_id, .. }) =
+        send(&sys, ADMIN, CompoundAction::QueueProposal { change })
+    else {
+        panic!("Unable to queue proposal");
+    };
+
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id }),
+        Err(CompoundError::ProposalNotReady(proposal_id))
+    );
+    skip_seconds(&sys, TIMELOCK_DELAY);
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id }),
+        Ok(CompoundEvent::CollateralFactorSet {
+            market: TOKEN_A.into(),
+            collateral_factor: Wad::from_percent(70),
+        })
+    );
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id }),
+        Err(CompoundError::ProposalNotQueued(proposal_id))
+    );
+
+    let state = state(&sys);
+    assert_eq!(
+        compound_state::market(&state, &TOKEN_A.into()).map(|market| market.collateral_factor),
+        Some(Wad::from_percent(70))
+    );
+    assert!(matches!(
+        state.proposals[..],
+        [(id, Proposal {
+            status: ProposalStatus::Executed { .. },
+            ..
+        })] if id == proposal_id
+    ));
+}
+
+#[test]
+fn cancelled_proposal_is_not_executed() {
+    let sys = System::new();
+    init(&sys);
+
+    let Ok(CompoundEvent::ProposalQueued { proposal_id, .. }) = send(
+        &sys,
+        ADMIN,
+        CompoundAction::QueueProposal {
+            change: ParameterChange::CloseFactor {
+                close_factor: Wad::from_percent(80),
+            },
+        },
+    ) else {
+        panic!("Unable to queue proposal");
+    };
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::CancelProposal { proposal_id }),
+        Ok(CompoundEvent::ProposalCancelled { proposal_id })
+    );
+    skip_seconds(&sys, TIMELOCK_DELAY);
+    assert_eq!(
+        send(&sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id }),
+        Err(CompoundError::ProposalNotQueued(proposal_id))
+    );
+    assert_eq!(state(&sys).close_factor, Wad::from_percent(50));
+}
+
+#[test]
+fn unsafe_parameters_are_rejected() {
+    let sys = System::new();
+    init(&sys);
+
+    for (change, error) in [
+        (
+            ParameterChange::CollateralFactor {
+                market: TOKEN_A.into(),
+                collateral_factor: Wad::from_percent(91),
+            },
+            CompoundError::InvalidCollateralFactor,
+        ),
+        (
+            ParameterChange::ReserveFactor {
+                market: TOKEN_A.into(),
+                reserve_factor: Wad::from_percent(60),
+            },
+            CompoundError::InvalidReserveFactor,
+        ),
+        (
+            ParameterChange::CloseFactor {
+                close_factor: Wad::ZERO,
+            },
+            CompoundError::InvalidCloseFactor,
+        ),
+        (
+            ParameterChange::LiquidationIncentive {
+                liquidation_incentive: Wad::ONE,
+            },
+            CompoundError::InvalidLiquidationIncentive,
+        ),
+        (
+            ParameterChange::RateModel {
+                market: TOKEN_A.into(),
+                rate_model: RateModel::Linear(LinearRateModel {
+                    base_rate: Wad::ZERO,
+                    multiplier: Wad::from_percent(2000),
+                }),
+            },
+            CompoundError::InvalidRateModel,
+        ),
+        (
+            ParameterChange::CollateralFactor {
+                market: NOT_LISTED.into(),
+                collateral_factor: Wad::from_percent(60),
+            },
+            CompoundError::MarketNotListed(NOT_LISTED.into()),
+        ),
+    ] {
+        assert_eq!(
+            send(&sys, ADMIN, CompoundAction::QueueProposal { change }),
+            Err(error)
+        );
+    }
+}
+
+#[test]
+fn admin_transfer_needs_acceptance() {
+    let sys = System::new();
+    init(&sys);
+
+    assert_eq!(
+        send(
+            &sys,
+            LENDER,
+            CompoundAction::TransferAdmin {
+                new_admin: LENDER.into()
+            }
+        ),
+        Err(CompoundError::Unauthorized)
+    );
+    assert_eq!(
+        send(
+            &sys,
+            ADMIN,
+            CompoundAction::TransferAdmin {
+                new_admin: LENDER.into()
+            }
+        ),
+        Ok(CompoundEvent::AdminTransferProposed {
+            pending_admin: LENDER.into()
+        })
+    );
+    assert_eq!(
+        send(&sys, BORROWER, CompoundAction::AcceptAdmin),
+        Err(CompoundError::Unauthorized)
+    );
+    assert_eq!(state(&sys).admin, ADMIN.into());
+
+    assert_eq!(
+        send(&sys, LENDER, CompoundAction::AcceptAdmin),
+        Ok(CompoundEvent::AdminTransferred {
+            old_admin: ADMIN.into(),
+            new_admin: LENDER.into(),
+        })
+    );
+    let state = state(&sys);
+    assert_eq!((state.admin, state.pending_admin), (LENDER.into(), None));
+    assert_eq!(
+        send(
+            &sys,
+            ADMIN,
+            CompoundAction::QueueProposal {
+                change: ParameterChange::AddMarket(market_config(20))
+            }
+        ),
+        Err(CompoundError::Unauthorized)
+    );
+}
+
+#[test]
+fn guardian_pauses_actions() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_B, 10_000);
+    lend(&sys, BORROWER, TOKEN_A, 1_000);
+    borrow(&sys, BORROWER, TOKEN_B, 100);
+
+    let set_paused = |from, action, paused| {
+        send(
+            &sys,
+            from,
+            CompoundAction::SetPaused {
+                market: TOKEN_B.into(),
+                action,
+                paused,
+            },
+        )
+    };
+    assert_eq!(
+        set_paused(LENDER, PausableAction::Borrow, true),
+        Err(CompoundError::Unauthorized)
+    );
+    for action in [
+        PausableAction::Lend,
+        PausableAction::Borrow,
+        PausableAction::Withdraw,
+    ] {
+        assert!(set_paused(GUARDIAN, action, true).is_ok());
+    }
+    assert!(compound_state::is_paused(
+        &state(&sys),
+        &TOKEN_B.into(),
+        PausableAction::Borrow
+    ));
+
+    assert_eq!(
+        send(
+            &sys,
+            BORROWER,
+            CompoundAction::BorrowTokens {
+                market: TOKEN_B.into(),
+                amount: Tokens(100),
+            }
+        ),
+        Err(CompoundError::ActionPaused {
+            market: TOKEN_B.into(),
+            action: PausableAction::Borrow,
+        })
+    );
+    assert_eq!(
+        send(
+            &sys,
+            LENDER,
+            CompoundAction::WithdrawTokens {
+                market: TOKEN_B.into(),
+                amount: Tokens(100),
+            }
+        ),
+        Err(CompoundError::ActionPaused {
+            market: TOKEN_B.into(),
+            action: PausableAction::Withdraw,
+        })
+    );
+    // погашение не приостанавливается, а другие рынки работают
+    send(
+        &sys,
+        BORROWER,
+        CompoundAction::RefundTokens {
+            market: TOKEN_B.into(),
+            amount: Tokens(50),
+        },
+    )
+    .expect("Refund failed");
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+
+    // возобновить действие может только администратор
+    assert_eq!(
+        set_paused(GUARDIAN, PausableAction::Borrow, false),
+        Err(CompoundError::Unauthorized)
+    );
+    assert!(set_paused(ADMIN, PausableAction::Borrow, false).is_ok());
+    borrow(&sys, BORROWER, TOKEN_B, 100);
+}
+
+#[test]
+fn admin_manages_reserves() {
+    let sys = System::new();
+    init(&sys);
+
+    let add = CompoundAction::AddReserves {
+        market: TOKEN_A.into(),
+        amount: Tokens(1_000),
+    };
+    assert_eq!(
+        send(&sys, LENDER, add.clone()),
+        Err(CompoundError::Unauthorized)
+    );
+    assert_eq!(
+        send(&sys, ADMIN, add),
+        Ok(CompoundEvent::ReservesAdded {
+            market: TOKEN_A.into(),
+            amount: Tokens(1_000),
+            total_reserves: Tokens(1_000),
+        })
+    );
+    assert_eq!(token_balance(&sys, TOKEN_A, COMPOUND), 1_000);
+
+    let reduce = |amount| CompoundAction::ReduceReserves {
+        market: TOKEN_A.into(),
+        amount: Tokens(amount),
+    };
+    assert_eq!(
+        send(&sys, ADMIN, reduce(1_001)),
+        Err(CompoundError::NotEnoughReserves)
+    );
+    assert_eq!(
+        send(&sys, ADMIN, reduce(400)),
+        Ok(CompoundEvent::ReservesReduced {
+            market: TOKEN_A.into(),
+            amount: Tokens(400),
+            total_reserves: Tokens(600),
+        })
+    );
+    assert_eq!(token_balance(&sys, TOKEN_A, ADMIN), USER_BALANCE - 600);
+
+    // резервы не принадлежат вкладчикам и не меняют курс ctoken
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+    assert_eq!(
+        user_assets(&sys, TOKEN_A, LENDER).lent_amount,
+        CTokens(1_000)
+    );
+}
+
+#[test]
+fn unknown_transaction() {
+    let sys = System::new();
+    init(&sys);
+
+    let resolve = CompoundAction::ResolveTransaction {
+        tx_id: 7,
+        transferred: false,
+    };
+    assert_eq!(
+        send(&sys, LENDER, resolve.clone()),
+        Err(CompoundError::Unauthorized)
+    );
+    assert_eq!(
+        send(&sys, ADMIN, resolve),
+        Err(CompoundError::TransactionNotFound(7))
+    );
+    assert!(state(&sys).transactions.is_empty());
+}
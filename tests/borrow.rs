@@ -0,0 +1,217 @@
+use compound_io::*;
+use gtest::System;
+use utils::*;
+
+mod utils;
+
+fn borrow_action(market: u64, amount: u128) -> CompoundAction {
+    CompoundAction::BorrowTokens {
+        market: market.into(),
+        amount: Tokens(amount),
+    }
+}
+
+fn account_liquidity(sys: &System, user: u64, market: u64) -> Option<AccountLiquidity> {
+    let StateReply::AccountLiquidity(liquidity) = read_state(
+        sys,
+        StateQuery::AccountLiquidity {
+            user: user.into(),
+            market: market.into(),
+        },
+    ) else {
+        panic!("Unexpected reply");
+    };
+    liquidity
+}
+
+// в рынке B есть свободные токены, у заемщика вклад 1000 токенов A в залоге
+fn setup(sys: &System) {
+    init(sys);
+    lend(sys, LENDER, TOKEN_B, 10_000);
+    lend(sys, BORROWER, TOKEN_A, 1_000);
+}
+
+#[test]
+fn borrow_against_collateral() {
+    let sys = System::new();
+    setup(&sys);
+
+    let Ok(CompoundEvent::TokensBorrowed {
+        market,
+        address,
+        amount,
+        ..
+    }) = send(&sys, BORROWER, borrow_action(TOKEN_B, 400))
+    else {
+        panic!("Borrow failed");
+    };
+    assert_eq!(
+        (market, address, amount),
+        (TOKEN_B.into(), BORROWER.into(), Tokens(400))
+    );
+
+    assert_eq!(token_balance(&sys, TOKEN_B, BORROWER), USER_BALANCE + 400);
+    assert_eq!(token_balance(&sys, TOKEN_B, COMPOUND), 9_600);
+    assert!(user_assets(&sys, TOKEN_B, BORROWER).borrowed_amount >= Tokens(400));
+
+    let liquidity = account_liquidity(&sys, BORROWER, TOKEN_B).expect("Market is listed");
+    assert_eq!(liquidity.borrow_limit, 500);
+    assert!(!liquidity.is_undercollateralized());
+}
+
+#[test]
+fn liquidity_limited_by_market() {
+    let sys = System::new();
+    init_with(
+        &sys,
+        CompoundInit {
+            markets: vec![
+                MarketConfig {
+                    borrow_cap: Tokens(300),
+                    ..market_config(TOKEN_A)
+                },
+                market_config(TOKEN_B),
+            ],
+            ..init_config()
+        },
+    );
+    lend(&sys, LENDER, TOKEN_B, 1_000);
+    lend(&sys, BORROWER, TOKEN_A, 10_000);
+
+    // залога хватает на 5000, но в рынке B всего 1000 свободных токенов
+    let liquidity = account_liquidity(&sys, BORROWER, TOKEN_B).expect("Market is listed");
+    assert_eq!(liquidity.available_to_borrow, Tokens(1_000));
+    // в рынке A свободных токенов много, но предел кредитов 300
+    let liquidity = account_liquidity(&sys, LENDER, TOKEN_A).expect("Market is listed");
+    assert_eq!(liquidity.available_to_borrow, Tokens(300));
+
+    // после займа вкладчик может вывести только оставшиеся в пуле токены
+    borrow(&sys, BORROWER, TOKEN_B, 600);
+    let liquidity = account_liquidity(&sys, LENDER, TOKEN_B).expect("Market is listed");
+    assert_eq!(liquidity.max_withdraw, Tokens(400));
+    assert_eq!(
+        send(
+            &sys,
+            LENDER,
+            CompoundAction::WithdrawTokens {
+                market: TOKEN_B.into(),
+                amount: Tokens(401),
+            }
+        ),
+        Err(CompoundError::NotEnoughCash)
+    );
+
+    assert_eq!(account_liquidity(&sys, BORROWER, NOT_LISTED), None);
+}
+
+#[test]
+fn borrow_fails_without_collateral() {
+    let sys = System::new();
+    setup(&sys);
+
+    assert_eq!(
+        send(&sys, LIQUIDATOR, borrow_action(TOKEN_B, 100)),
+        Err(CompoundError::NoAssets(LIQUIDATOR.into()))
+    );
+    // предел займа - половина залога
+    assert_eq!(
+        send(&sys, BORROWER, borrow_action(TOKEN_B, 501)),
+        Err(CompoundError::InsufficientCollateral)
+    );
+    assert_eq!(
+        send(&sys, BORROWER, borrow_action(TOKEN_A, 600)),
+        Err(CompoundError::InsufficientCollateral)
+    );
+    assert_eq!(token_balance(&sys, TOKEN_B, BORROWER), USER_BALANCE);
+}
+
+#[test]
+fn borrow_fails_without_cash() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_B, 100);
+    lend(&sys, BORROWER, TOKEN_A, 1_000);
+
+    assert_eq!(
+        send(&sys, BORROWER, borrow_action(TOKEN_B, 101)),
+        Err(CompoundError::NotEnoughCash)
+    );
+    assert_eq!(
+        send(&sys, BORROWER, borrow_action(TOKEN_B, 0)),
+        Err(CompoundError::ZeroAmount)
+    );
+}
+
+#[test]
+fn borrow_respects_borrow_cap() {
+    let sys = System::new();
+    setup(&sys);
+
+    change_parameter(
+        &sys,
+        ParameterChange::BorrowCap {
+            market: TOKEN_B.into(),
+            borrow_cap: Tokens(300),
+        },
+    );
+    assert_eq!(
+        send(&sys, BORROWER, borrow_action(TOKEN_B, 301)),
+        Err(CompoundError::BorrowCapExceeded(TOKEN_B.into()))
+    );
+    borrow(&sys, BORROWER, TOKEN_B, 300);
+}
+
+#[test]
+fn exit_market_removes_collateral() {
+    let sys = System::new();
+    setup(&sys);
+    borrow(&sys, BORROWER, TOKEN_B, 400);
+
+    // без вклада в A долг в B ничем не обеспечен
+    assert_eq!(
+        send(
+            &sys,
+            BORROWER,
+            CompoundAction::ExitMarket {
+                market: TOKEN_A.into()
+            }
+        ),
+        Err(CompoundError::InsufficientCollateral)
+    );
+
+    // вклад в рынке B не в залоге, поэтому занимать под него нельзя
+    lend(&sys, LIQUIDATOR, TOKEN_B, 1_000);
+    assert_eq!(
+        send(
+            &sys,
+            LIQUIDATOR,
+            CompoundAction::ExitMarket {
+                market: TOKEN_B.into()
+            }
+        ),
+        Ok(CompoundEvent::MarketExited {
+            market: TOKEN_B.into(),
+            address: LIQUIDATOR.into(),
+        })
+    );
+    assert_eq!(
+        send(&sys, LIQUIDATOR, borrow_action(TOKEN_A, 1)),
+        Err(CompoundError::InsufficientCollateral)
+    );
+
+    assert_eq!(
+        send(
+            &sys,
+            LIQUIDATOR,
+            CompoundAction::EnterMarket {
+                market: TOKEN_B.into()
+            }
+        ),
+        Ok(CompoundEvent::MarketEntered {
+            market: TOKEN_B.into(),
+            address: LIQUIDATOR.into(),
+        })
+    );
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+    borrow(&sys, LIQUIDATOR, TOKEN_A, 400);
+}
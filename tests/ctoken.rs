@@ -0,0 +1,172 @@
+use compound_io::{ft::*, *};
+use gtest::System;
+use utils::*;
+
+mod utils;
+
+fn transfer_action(from: u64, to: u64, amount: u128) -> FTAction {
+    FTAction::Transfer {
+        from: from.into(),
+        to: to.into(),
+        amount,
+    }
+}
+
+#[test]
+fn ctokens_follow_ft_interface() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+    lend(&sys, BORROWER, TOKEN_A, 500);
+
+    // у каждого рынка свой контракт ctoken
+    assert_ne!(ctoken(&sys, TOKEN_A), ctoken(&sys, TOKEN_B));
+    assert_eq!(
+        send_ctoken(&sys, ADMIN, TOKEN_A, FTAction::TotalSupply),
+        Ok(FTEvent::TotalSupply(1_500))
+    );
+    assert_eq!(
+        send_ctoken(&sys, ADMIN, TOKEN_A, FTAction::BalanceOf(LENDER.into())),
+        Ok(FTEvent::Balance(1_000))
+    );
+    assert_eq!(
+        send_ctoken(&sys, ADMIN, TOKEN_B, FTAction::TotalSupply),
+        Ok(FTEvent::TotalSupply(0))
+    );
+
+    assert_eq!(
+        send_ctoken(
+            &sys,
+            LENDER,
+            TOKEN_A,
+            transfer_action(LENDER, LIQUIDATOR, 300)
+        ),
+        Ok(FTEvent::Transfer {
+            from: LENDER.into(),
+            to: LIQUIDATOR.into(),
+            amount: 300,
+        })
+    );
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LENDER), 700);
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LIQUIDATOR), 300);
+
+    // полученные ctokens выводятся как обычный вклад
+    send(
+        &sys,
+        LIQUIDATOR,
+        CompoundAction::WithdrawTokens {
+            market: TOKEN_A.into(),
+            amount: Tokens(300),
+        },
+    )
+    .expect("Withdraw failed");
+    assert_eq!(token_balance(&sys, TOKEN_A, LIQUIDATOR), USER_BALANCE + 300);
+
+    let error = send_ctoken(&sys, LENDER, TOKEN_A, FTAction::Mint(1)).unwrap_err();
+    assert!(error.contains("Unauthorized"), "{error}");
+    let error = send_ctoken(
+        &sys,
+        LENDER,
+        TOKEN_A,
+        transfer_action(LENDER, LIQUIDATOR, 701),
+    )
+    .unwrap_err();
+    assert!(error.contains("AmountTooBig"), "{error}");
+}
+
+#[test]
+fn only_ctokens_reach_controller() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+
+    // напрямую контракт compound не принимает действия ctokens даже от владельца
+    assert_eq!(
+        send(
+            &sys,
+            LENDER,
+            CompoundAction::CToken {
+                sender: LENDER.into(),
+                action: transfer_action(LENDER, LIQUIDATOR, 100),
+            }
+        ),
+        Err(CompoundError::Unauthorized)
+    );
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LENDER), 1_000);
+}
+
+#[test]
+fn ctokens_transfer_by_allowance() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+
+    let error = send_ctoken(
+        &sys,
+        LIQUIDATOR,
+        TOKEN_A,
+        transfer_action(LENDER, LIQUIDATOR, 100),
+    )
+    .unwrap_err();
+    assert!(error.contains("NotEnoughAllowance"), "{error}");
+    assert_eq!(
+        send_ctoken(
+            &sys,
+            LENDER,
+            TOKEN_A,
+            FTAction::Approve {
+                to: LIQUIDATOR.into(),
+                amount: 100,
+            }
+        ),
+        Ok(FTEvent::Approve {
+            from: LENDER.into(),
+            to: LIQUIDATOR.into(),
+            amount: 100,
+        })
+    );
+
+    send_ctoken(
+        &sys,
+        LIQUIDATOR,
+        TOKEN_A,
+        transfer_action(LENDER, BORROWER, 100),
+    )
+    .expect("Transfer failed");
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LENDER), 900);
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, BORROWER), 100);
+    assert!(send_ctoken(
+        &sys,
+        LIQUIDATOR,
+        TOKEN_A,
+        transfer_action(LENDER, LIQUIDATOR, 1)
+    )
+    .is_err());
+}
+
+#[test]
+fn ctokens_transfer_keeps_debt_collateralized() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_B, 10_000);
+    lend(&sys, BORROWER, TOKEN_A, 1_000);
+    borrow(&sys, BORROWER, TOKEN_B, 400);
+
+    // под долг 400 при коэффициенте 50% в залоге должно остаться 800 токенов
+    let error = send_ctoken(
+        &sys,
+        BORROWER,
+        TOKEN_A,
+        transfer_action(BORROWER, LIQUIDATOR, 201),
+    )
+    .unwrap_err();
+    assert!(error.contains("InsufficientCollateral"), "{error}");
+    send_ctoken(
+        &sys,
+        BORROWER,
+        TOKEN_A,
+        transfer_action(BORROWER, LIQUIDATOR, 150),
+    )
+    .expect("Transfer failed");
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, BORROWER), 850);
+}
@@ -0,0 +1,249 @@
+// случайные последовательности действий многих пользователей: после каждого шага
+// проверяем, что учет контракта сходится с балансами токенов
+
+use compound_io::{ft::FTAction, *};
+use gstd::{collections::BTreeMap, ActorId};
+use gtest::System;
+use proptest::prelude::*;
+use utils::*;
+
+mod utils;
+
+const MARKETS: [u64; 2] = [TOKEN_A, TOKEN_B];
+
+// действие пользователя, перед которым проходит `seconds` секунд
+#[derive(Debug, Clone)]
+struct Step {
+    seconds: u64,
+    user: u64,
+    action: Action,
+}
+
+#[derive(Debug, Clone)]
+enum Action {
+    Compound(CompoundAction),
+    CToken { market: u64, action: FTAction }, // отправляется контракту ctoken рынка
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    let user = || prop::sample::select(USERS.to_vec());
+    let seconds = prop_oneof![7 => Just(0), 1 => 1..7_200u64];
+    (
+        seconds,
+        user(),
+        user(),
+        0..5u8,
+        prop::sample::select(MARKETS.to_vec()),
+        1..20_000u128,
+    )
+        .prop_map(|(seconds, user, recipient, kind, market, amount)| {
+            let action = match kind {
+                4 => Action::CToken {
+                    market,
+                    action: FTAction::Transfer {
+                        from: user.into(),
+                        to: recipient.into(),
+                        amount,
+                    },
+                },
+                _ => {
+                    let (market, amount) = (market.into(), Tokens(amount));
+                    Action::Compound(match kind {
+                        0 => CompoundAction::LendTokens { market, amount },
+                        1 => CompoundAction::BorrowTokens { market, amount },
+                        2 => CompoundAction::RefundTokens { market, amount },
+                        _ => CompoundAction::WithdrawTokens { market, amount },
+                    })
+                }
+            };
+            Step {
+                seconds,
+                user,
+                action,
+            }
+        })
+}
+
+// ctokens, выпущенные и сожженные каждому пользователю по событиям и переводам
+#[derive(Default)]
+struct History {
+    ctokens: BTreeMap<(ActorId, ActorId), u128>,
+}
+
+impl History {
+    // учитывает шаг, сверяя выпуск ctokens с курсами до и после шага
+    fn record(
+        &mut self,
+        sys: &System,
+        user: u64,
+        action: &CompoundAction,
+        before: &CompoundState,
+        reply: &Result<CompoundEvent, CompoundError>,
+    ) {
+        match (action, reply) {
+            (
+                CompoundAction::LendTokens { market, amount },
+                Ok(CompoundEvent::TokensLended { ctokens_amount, .. }),
+            ) => {
+                // ctokens выпускаются по курсу в момент перевода: округление вниз оставляет
+                // курс после вклада не ниже него, поэтому по этому курсу выпущенные ctokens
+                // не дороже внесенных токенов, а еще один ctoken уже дороже
+                let minted = ctokens_amount.0;
+                let before = market_state(before, market);
+                if before.total_ctokens.is_zero() {
+                    let initial_rate = Wad::ONE.div(before.ctoken_rate, Rounding::Down);
+                    assert_eq!(minted, amount.to_ctokens(initial_rate, Rounding::Down).0);
+                } else {
+                    let rate_after = market_state(&state(sys), market).exchange_rate;
+                    assert!(rate_after.scale(minted, Rounding::Down) <= amount.0);
+                    assert!(rate_after.scale(minted + 1, Rounding::Up) > amount.0);
+                }
+                *self.ctokens.entry((*market, user.into())).or_default() += ctokens_amount.0;
+            }
+            (CompoundAction::WithdrawTokens { market, amount }, Ok(_)) => {
+                // сжигается округленная вверх доля вклада по курсу в момент вывода: проценты
+                // только поднимают курс, а округление вверх не дает ему упасть после вывода,
+                // поэтому сожженные ctokens покрывают вывод по курсу после шага, а без одного
+                // ctoken не покрывают его уже по курсу до шага
+                let after = state(sys);
+                let balance = compound_state::user_assets(&after, market, &user.into())
+                    .map(|assets| assets.lent_amount.0)
+                    .unwrap_or_default();
+                let entry = self.ctokens.entry((*market, user.into())).or_default();
+                let burned = *entry - balance;
+                let rate_before = market_state(before, market).exchange_rate;
+                assert!(burned > 0, "Withdraw burned no ctokens");
+                assert!(rate_before.scale(burned - 1, Rounding::Down) < amount.0);
+                let after = market_state(&after, market);
+                if !after.total_ctokens.is_zero() {
+                    assert!(after.exchange_rate.scale(burned, Rounding::Down) >= amount.0);
+                }
+                *entry = balance;
+            }
+            (CompoundAction::BorrowTokens { market, .. }, Ok(_)) => {
+                // после успешного займа долг не превышает залог с учетом коэффициентов
+                let liquidity =
+                    compound_state::account_liquidity(&state(sys), &user.into(), market)
+                        .expect("Market is listed");
+                assert!(
+                    liquidity.borrow_value <= liquidity.borrow_limit,
+                    "Borrow exceeds collateral: {liquidity:?}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    // учитывает успешный перевод через контракт ctoken
+    fn transferred(&mut self, sys: &System, market: u64, action: &FTAction) {
+        let FTAction::Transfer { from, to, amount } = action else {
+            return;
+        };
+        let market = market.into();
+        *self.ctokens.entry((market, *from)).or_default() -= amount;
+        *self.ctokens.entry((market, *to)).or_default() += amount;
+        // перевод залога не оставляет долги отправителя без обеспечения
+        let state = state(sys);
+        let sender = compound_state::user_assets(&state, &market, from).cloned();
+        if sender.is_some_and(|assets| assets.is_collateral) {
+            let liquidity =
+                compound_state::account_liquidity(&state, from, &market).expect("Market is listed");
+            assert!(
+                !liquidity.is_undercollateralized(),
+                "Transfer leaves debt without collateral: {liquidity:?}"
+            );
+        }
+    }
+}
+
+fn market_state(state: &CompoundState, market: &ActorId) -> MarketState {
+    compound_state::market(state, market)
+        .cloned()
+        .expect("Market is not listed")
+}
+
+fn check_invariants(sys: &System, history: &History) {
+    let state = state(sys);
+    assert!(state.locked_accounts.is_empty());
+    assert!(state.transactions.is_empty());
+
+    for token in MARKETS {
+        let market = market_state(&state, &token.into());
+        let assets: BTreeMap<ActorId, Assets> = market.user_assets.iter().cloned().collect();
+
+        // вклады пользователей в сумме равны выпущенным ctokens
+        let lent: u128 = assets.values().map(|assets| assets.lent_amount.0).sum();
+        assert_eq!(lent, market.total_ctokens.0);
+        let history_total: u128 = history
+            .ctokens
+            .iter()
+            .filter(|((market, _), _)| *market == token.into())
+            .map(|(_, amount)| amount)
+            .sum();
+        assert_eq!(history_total, market.total_ctokens.0);
+
+        for user in USERS {
+            let lent_amount = assets
+                .get(&user.into())
+                .map(|assets| assets.lent_amount.0)
+                .unwrap_or_default();
+            assert_eq!(
+                history
+                    .ctokens
+                    .get(&(token.into(), user.into()))
+                    .copied()
+                    .unwrap_or_default(),
+                lent_amount
+            );
+        }
+
+        // свободные токены пула лежат на счете контракта, токены никуда не пропадают
+        assert_eq!(token_balance(sys, token, COMPOUND), market.total_cash.0);
+        let users_balance: u128 = USERS
+            .iter()
+            .map(|user| token_balance(sys, token, *user))
+            .sum();
+        assert_eq!(
+            users_balance + market.total_cash.0,
+            USER_BALANCE * USERS.len() as u128
+        );
+        assert!(market.total_reserves <= market.total_cash + market.total_borrows);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        // каждый прогон поднимает контракт заново, поэтому по умолчанию их немного
+        cases: std::env::var("PROPTEST_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(8),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn pool_stays_consistent(steps in prop::collection::vec(step(), 1..40)) {
+        let sys = System::new();
+        init(&sys);
+        let mut history = History::default();
+
+        for Step { seconds, user, action } in steps {
+            if seconds > 0 {
+                skip_seconds(&sys, seconds);
+            }
+            match action {
+                Action::Compound(action) => {
+                    let before = state(&sys);
+                    let reply = send(&sys, user, action.clone());
+                    history.record(&sys, user, &action, &before, &reply);
+                }
+                Action::CToken { market, action } => {
+                    if send_ctoken(&sys, user, market, action.clone()).is_ok() {
+                        history.transferred(&sys, market, &action);
+                    }
+                }
+            }
+            check_invariants(&sys, &history);
+        }
+    }
+}
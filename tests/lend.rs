@@ -0,0 +1,101 @@
+use compound_io::*;
+use gtest::System;
+use utils::*;
+
+mod utils;
+
+fn lend_action(market: u64, amount: u128) -> CompoundAction {
+    CompoundAction::LendTokens {
+        market: market.into(),
+        amount: Tokens(amount),
+    }
+}
+
+#[test]
+fn lend_mints_ctokens() {
+    let sys = System::new();
+    init(&sys);
+
+    assert_eq!(
+        send(&sys, LENDER, lend_action(TOKEN_A, 1_000)),
+        Ok(CompoundEvent::TokensLended {
+            market: TOKEN_A.into(),
+            address: LENDER.into(),
+            amount: Tokens(1_000),
+            ctokens_amount: CTokens(1_000),
+        })
+    );
+
+    assert_eq!(token_balance(&sys, TOKEN_A, LENDER), USER_BALANCE - 1_000);
+    assert_eq!(token_balance(&sys, TOKEN_A, COMPOUND), 1_000);
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LENDER), 1_000);
+
+    let assets = user_assets(&sys, TOKEN_A, LENDER);
+    assert_eq!(assets.lent_amount, CTokens(1_000));
+    assert!(assets.is_collateral);
+
+    let market = compound_state::market(&state(&sys), &TOKEN_A.into())
+        .cloned()
+        .expect("Market is not listed");
+    assert_eq!(market.total_cash, Tokens(1_000));
+    assert_eq!(market.total_ctokens, CTokens(1_000));
+}
+
+#[test]
+fn lend_adds_to_existing_deposit() {
+    let sys = System::new();
+    init(&sys);
+
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+    lend(&sys, LENDER, TOKEN_A, 500);
+
+    assert_eq!(
+        user_assets(&sys, TOKEN_A, LENDER).lent_amount,
+        CTokens(1_500)
+    );
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LENDER), 1_500);
+}
+
+#[test]
+fn lend_fails() {
+    let sys = System::new();
+    init(&sys);
+
+    assert_eq!(
+        send(&sys, LENDER, lend_action(TOKEN_A, 0)),
+        Err(CompoundError::ZeroAmount)
+    );
+    assert_eq!(
+        send(&sys, LENDER, lend_action(NOT_LISTED, 1_000)),
+        Err(CompoundError::MarketNotListed(NOT_LISTED.into()))
+    );
+
+    // у пользователя не хватает токенов: перевод не проходит, ctokens не выпускаются
+    assert_eq!(
+        send(&sys, LENDER, lend_action(TOKEN_A, USER_BALANCE + 1)),
+        Err(CompoundError::TransferFailed)
+    );
+    assert_eq!(token_balance(&sys, TOKEN_A, LENDER), USER_BALANCE);
+    assert_eq!(user_assets(&sys, TOKEN_A, LENDER), Assets::default());
+}
+
+#[test]
+fn lend_respects_supply_cap() {
+    let sys = System::new();
+    init(&sys);
+
+    change_parameter(
+        &sys,
+        ParameterChange::SupplyCap {
+            market: TOKEN_A.into(),
+            supply_cap: Tokens(1_500),
+        },
+    );
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+
+    assert_eq!(
+        send(&sys, BORROWER, lend_action(TOKEN_A, 501)),
+        Err(CompoundError::SupplyCapExceeded(TOKEN_A.into()))
+    );
+    lend(&sys, BORROWER, TOKEN_A, 500);
+}
@@ -0,0 +1,97 @@
+use compound_io::*;
+use gtest::System;
+use utils::*;
+
+mod utils;
+
+fn liquidate_action(borrower: u64, repay_amount: u128) -> CompoundAction {
+    CompoundAction::Liquidate {
+        borrower: borrower.into(),
+        repay_market: TOKEN_B.into(),
+        collateral_market: TOKEN_A.into(),
+        repay_amount: Tokens(repay_amount),
+    }
+}
+
+// заемщик занял 400 токенов B под 1000 токенов A, обе цены равны единице
+fn setup(sys: &System) {
+    init(sys);
+    lend(sys, LENDER, TOKEN_B, 10_000);
+    lend(sys, BORROWER, TOKEN_A, 1_000);
+    borrow(sys, BORROWER, TOKEN_B, 400);
+}
+
+#[test]
+fn liquidate_after_price_rise() {
+    let sys = System::new();
+    setup(&sys);
+
+    // долг подорожал вдвое и превысил предел залога в 500
+    set_price(&sys, TOKEN_B, Wad::from_percent(200));
+
+    // погашенные 200 токенов B стоят 400 токенов A, бонус ликвидатора 8%
+    assert_eq!(
+        send(&sys, LIQUIDATOR, liquidate_action(BORROWER, 200)),
+        Ok(CompoundEvent::Liquidated {
+            liquidator: LIQUIDATOR.into(),
+            borrower: BORROWER.into(),
+            repay_market: TOKEN_B.into(),
+            collateral_market: TOKEN_A.into(),
+            repay_amount: Tokens(200),
+            ctokens_seized: CTokens(432),
+        })
+    );
+    assert_eq!(token_balance(&sys, TOKEN_B, LIQUIDATOR), USER_BALANCE - 200);
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LIQUIDATOR), 432);
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, BORROWER), 568);
+    assert_eq!(
+        user_assets(&sys, TOKEN_A, BORROWER).lent_amount,
+        CTokens(568)
+    );
+    assert_eq!(
+        user_assets(&sys, TOKEN_A, LIQUIDATOR).lent_amount,
+        CTokens(432)
+    );
+    assert!(user_assets(&sys, TOKEN_B, BORROWER).borrowed_amount <= Tokens(201));
+}
+
+#[test]
+fn liquidate_fails() {
+    let sys = System::new();
+    setup(&sys);
+
+    assert_eq!(
+        send(&sys, LIQUIDATOR, liquidate_action(BORROWER, 100)),
+        Err(CompoundError::NotUndercollateralized(BORROWER.into()))
+    );
+    assert_eq!(
+        send(&sys, BORROWER, liquidate_action(BORROWER, 100)),
+        Err(CompoundError::SelfLiquidation)
+    );
+
+    set_price(&sys, TOKEN_B, Wad::from_percent(200));
+    assert_eq!(
+        send(&sys, LIQUIDATOR, liquidate_action(BORROWER, 0)),
+        Err(CompoundError::ZeroAmount)
+    );
+    // за раз можно погасить не больше половины долга
+    assert_eq!(
+        send(&sys, LIQUIDATOR, liquidate_action(BORROWER, 250)),
+        Err(CompoundError::CloseFactorExceeded)
+    );
+    assert_eq!(token_balance(&sys, TOKEN_B, LIQUIDATOR), USER_BALANCE);
+}
+
+#[test]
+fn stale_price_falls_back() {
+    let sys = System::new();
+    setup(&sys);
+    set_price(&sys, TOKEN_B, Wad::from_percent(200));
+
+    // цена оракула устарела, действует резервная цена и долг снова обеспечен
+    skip_seconds(&sys, init_config().max_price_age + 1);
+    assert_eq!(
+        send(&sys, LIQUIDATOR, liquidate_action(BORROWER, 100)),
+        Err(CompoundError::NotUndercollateralized(BORROWER.into()))
+    );
+}
@@ -0,0 +1,83 @@
+use compound_io::*;
+use gtest::System;
+use utils::*;
+
+mod utils;
+
+fn refund_action(market: u64, amount: u128) -> CompoundAction {
+    CompoundAction::RefundTokens {
+        market: market.into(),
+        amount: Tokens(amount),
+    }
+}
+
+fn borrow_amount(sys: &System) -> Tokens {
+    compound_state::borrow_amount(&state(sys), &TOKEN_B.into(), &BORROWER.into())
+}
+
+// заемщик занял 400 токенов B под вклад в 1000 токенов A
+fn setup(sys: &System) {
+    init(sys);
+    lend(sys, LENDER, TOKEN_B, 10_000);
+    lend(sys, BORROWER, TOKEN_A, 1_000);
+    borrow(sys, BORROWER, TOKEN_B, 400);
+}
+
+#[test]
+fn refund_reduces_debt() {
+    let sys = System::new();
+    setup(&sys);
+
+    assert_eq!(
+        send(&sys, BORROWER, refund_action(TOKEN_B, 150)),
+        Ok(CompoundEvent::TokensRefunded {
+            market: TOKEN_B.into(),
+            address: BORROWER.into(),
+            amount: Tokens(150),
+        })
+    );
+    assert_eq!(token_balance(&sys, TOKEN_B, BORROWER), USER_BALANCE + 250);
+    assert!(borrow_amount(&sys) < Tokens(400));
+}
+
+#[test]
+fn debt_grows_with_interest() {
+    let sys = System::new();
+    setup(&sys);
+
+    skip_seconds(&sys, 30 * 24 * 60 * 60 / 100);
+    assert!(borrow_amount(&sys) > Tokens(400));
+}
+
+#[test]
+fn refund_of_whole_debt_leaves_block_interest() {
+    let sys = System::new();
+    setup(&sys);
+
+    // проценты за блок с погашением меньше токена, но долг округляется вверх
+    let debt = borrow_amount(&sys);
+    send(&sys, BORROWER, refund_action(TOKEN_B, debt.0)).expect("Refund failed");
+    assert!(borrow_amount(&sys) <= Tokens(1));
+    assert!(compound_state::market(&state(&sys), &TOKEN_B.into())
+        .is_some_and(|market| market.total_borrows <= Tokens(1)));
+}
+
+#[test]
+fn refund_fails() {
+    let sys = System::new();
+    setup(&sys);
+
+    assert_eq!(
+        send(&sys, BORROWER, refund_action(TOKEN_B, 0)),
+        Err(CompoundError::ZeroAmount)
+    );
+    assert_eq!(
+        send(&sys, BORROWER, refund_action(TOKEN_B, 1_000)),
+        Err(CompoundError::AmountTooBig)
+    );
+    assert_eq!(
+        send(&sys, LIQUIDATOR, refund_action(TOKEN_B, 100)),
+        Err(CompoundError::NoAssets(LIQUIDATOR.into()))
+    );
+    assert_eq!(token_balance(&sys, TOKEN_B, BORROWER), USER_BALANCE + 400);
+}
@@ -0,0 +1,283 @@
+// общее окружение тестов: контракт compound, два рынка с фунгибельными токенами-заглушками
+// и тестовый оракул цен; ctokens рынков выпускает сам контракт, а их контракты с интерфейсом
+// fungible-token он создает из загруженного кода `compound_ctoken`
+
+#![allow(dead_code)]
+
+use compound_io::{ft::*, *};
+use gstd::{
+    codec::{Decode, Encode},
+    collections::BTreeMap,
+    ActorId,
+};
+use gtest::{constants::*, Program, System, WasmProgram};
+
+pub const ADMIN: u64 = DEFAULT_USER_ALICE;
+pub const LENDER: u64 = DEFAULT_USER_BOB;
+pub const BORROWER: u64 = DEFAULT_USER_CHARLIE;
+pub const LIQUIDATOR: u64 = DEFAULT_USER_EVE;
+pub const GUARDIAN: u64 = 50;
+
+pub const COMPOUND: u64 = 1;
+pub const ORACLE: u64 = 2;
+pub const TOKEN_A: u64 = 10;
+pub const TOKEN_B: u64 = 12;
+pub const NOT_LISTED: u64 = 14; // токен без рынка
+
+pub const USER_BALANCE: u128 = 1_000_000; // токенов каждого рынка у каждого пользователя
+pub const TIMELOCK_DELAY: u64 = timelock::MIN_TIMELOCK_DELAY; // секунд
+
+pub const USERS: [u64; 8] = [ADMIN, LENDER, BORROWER, LIQUIDATOR, 60, 61, 62, 63];
+
+// фунгибельный токен без разрешений на перевод: переводит кто угодно, но не больше баланса;
+// начальные балансы приходят в сообщении инициализации
+#[derive(Debug, Clone, Default)]
+pub struct MockFt {
+    balances: BTreeMap<ActorId, u128>,
+}
+
+impl WasmProgram for MockFt {
+    fn init(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        let balances: Vec<(ActorId, u128)> =
+            Decode::decode(&mut &payload[..]).map_err(|_| "Unable to decode balances")?;
+        self.balances = balances.into_iter().collect();
+        Ok(None)
+    }
+
+    fn handle(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        let action =
+            FTAction::decode(&mut &payload[..]).map_err(|_| "Unable to decode FTAction")?;
+        let event = match action {
+            FTAction::Transfer { from, to, amount } => {
+                let from_balance = self.balances.entry(from).or_default();
+                if *from_balance < amount {
+                    return Err("Not enough balance");
+                }
+                *from_balance -= amount;
+                *self.balances.entry(to).or_default() += amount;
+                FTEvent::Transfer { from, to, amount }
+            }
+            FTAction::BalanceOf(account) => {
+                FTEvent::Balance(self.balances.get(&account).copied().unwrap_or_default())
+            }
+            FTAction::TotalSupply => FTEvent::TotalSupply(self.balances.values().sum()),
+            _ => return Err("Unsupported action"),
+        };
+        Ok(Some(event.encode()))
+    }
+
+    fn clone_boxed(&self) -> Box<dyn WasmProgram> {
+        Box::new(self.clone())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Ok(self
+            .balances
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>()
+            .encode())
+    }
+}
+
+pub fn market_config(token: u64) -> MarketConfig {
+    MarketConfig {
+        token_address: token.into(),
+        collateral_factor: Wad::from_percent(50),
+        rate_model: RateModel::Linear(LinearRateModel {
+            base_rate: Wad::from_percent(2),
+            multiplier: Wad::from_percent(20),
+        }),
+        reserve_factor: Wad::from_percent(10),
+        ctoken_rate: Wad::ONE,
+        fallback_price: Wad::ONE,
+        ..Default::default()
+    }
+}
+
+pub fn init_config() -> CompoundInit {
+    CompoundInit {
+        close_factor: Wad::from_percent(50),
+        liquidation_incentive: Wad::from_percent(8),
+        oracle: ORACLE.into(),
+        max_price_age: 3600,
+        pause_guardian: GUARDIAN.into(),
+        timelock_delay: TIMELOCK_DELAY,
+        markets: vec![market_config(TOKEN_A), market_config(TOKEN_B)],
+        ..Default::default() // код ctoken загружает `init_with`
+    }
+}
+
+// разворачивает токены, оракул и контракт с рынками A и B
+pub fn init(sys: &System) {
+    init_with(sys, init_config());
+}
+
+pub fn init_with(sys: &System, config: CompoundInit) {
+    sys.init_logger();
+    for id in [GUARDIAN, 60, 61, 62, 63] {
+        sys.mint_to(id, DEFAULT_USERS_INITIAL_BALANCE);
+    }
+
+    let user_balances: Vec<(ActorId, u128)> = USERS
+        .iter()
+        .map(|user| ((*user).into(), USER_BALANCE))
+        .collect();
+    for id in [TOKEN_A, TOKEN_B] {
+        let token = Program::mock_with_id(sys, id, MockFt::default());
+        let message_id = token.send(ADMIN, user_balances.clone());
+        assert!(sys.run_next_block().succeed.contains(&message_id));
+    }
+
+    let oracle = Program::from_binary_with_id(sys, ORACLE, mock_oracle::WASM_BINARY_OPT);
+    let message_id = oracle.send_bytes(ADMIN, []);
+    assert!(sys.run_next_block().succeed.contains(&message_id));
+
+    let compound = Program::current_with_id(sys, COMPOUND);
+    let config = CompoundInit {
+        ctoken_code: sys.submit_code(compound_ctoken::WASM_BINARY_OPT),
+        ..config
+    };
+    // каждый созданный контракт ctoken получает от compound экзистенциальный депозит
+    let message_id = compound.send_with_value(ADMIN, config, 10 * EXISTENTIAL_DEPOSIT);
+    assert!(sys.run_next_block().succeed.contains(&message_id));
+}
+
+// отправляет действие и ждет ответа контракта
+pub fn send(
+    sys: &System,
+    from: u64,
+    action: CompoundAction,
+) -> Result<CompoundEvent, CompoundError> {
+    let compound = sys.get_program(COMPOUND).expect("Compound is not deployed");
+    let message_id = compound.send(from, action);
+    let result = sys.run_next_block();
+    let reply = result
+        .log()
+        .iter()
+        .find(|log| log.reply_to() == Some(message_id))
+        .expect("No reply from compound");
+    Decode::decode(&mut reply.payload()).expect("Unable to decode reply")
+}
+
+pub fn lend(sys: &System, user: u64, market: u64, amount: u128) {
+    send(
+        sys,
+        user,
+        CompoundAction::LendTokens {
+            market: market.into(),
+            amount: Tokens(amount),
+        },
+    )
+    .expect("Lend failed");
+}
+
+pub fn borrow(sys: &System, user: u64, market: u64, amount: u128) {
+    send(
+        sys,
+        user,
+        CompoundAction::BorrowTokens {
+            market: market.into(),
+            amount: Tokens(amount),
+        },
+    )
+    .expect("Borrow failed");
+}
+
+pub fn set_price(sys: &System, token: u64, price: Wad) {
+    let oracle = sys.get_program(ORACLE).expect("Oracle is not deployed");
+    let message_id = oracle.send(
+        ADMIN,
+        oracle::OracleAction::SetPrice {
+            token: token.into(),
+            price,
+        },
+    );
+    assert!(sys.run_next_block().succeed.contains(&message_id));
+}
+
+// проводит изменение параметров через очередь с задержкой
+pub fn change_parameter(sys: &System, change: ParameterChange) -> CompoundEvent {
+    let Ok(CompoundEvent::ProposalQueued { proposal_id, .. }) =
+        send(sys, ADMIN, CompoundAction::QueueProposal { change })
+    else {
+        panic!("Unable to queue proposal");
+    };
+    skip_seconds(sys, TIMELOCK_DELAY);
+    send(sys, ADMIN, CompoundAction::ExecuteProposal { proposal_id })
+        .expect("Unable to execute proposal")
+}
+
+// пропускает блоки, пока не пройдет `seconds` секунд
+pub fn skip_seconds(sys: &System, seconds: u64) {
+    let blocks = seconds * 1000 / BLOCK_DURATION_IN_MSECS + 1;
+    sys.run_to_block(sys.block_height() + blocks as u32);
+}
+
+pub fn token_balance(sys: &System, token: u64, account: u64) -> u128 {
+    let balances: Vec<(ActorId, u128)> = sys
+        .get_program(token)
+        .expect("Token is not deployed")
+        .read_state(())
+        .expect("Unable to read balances");
+    balances
+        .into_iter()
+        .find_map(|(id, balance)| (id == account.into()).then_some(balance))
+        .unwrap_or_default()
+}
+
+// ctokens пользователя в рынке, их баланс ведет сам контракт
+pub fn ctoken_balance(sys: &System, market: u64, account: u64) -> u128 {
+    user_assets(sys, market, account).lent_amount.0
+}
+
+// контракт ctoken рынка, который создал compound
+pub fn ctoken(sys: &System, market: u64) -> ActorId {
+    compound_state::market(&state(sys), &market.into())
+        .expect("Market is not listed")
+        .ctoken
+}
+
+// отправляет действие fungible-token контракту ctoken рынка; ошибка - текст паники ctoken
+pub fn send_ctoken(
+    sys: &System,
+    from: u64,
+    market: u64,
+    action: FTAction,
+) -> Result<FTEvent, String> {
+    let ctoken = sys
+        .get_program(ctoken(sys, market))
+        .expect("Ctoken is not deployed");
+    let message_id = ctoken.send(from, action);
+    let result = sys.run_next_block();
+    let reply = result
+        .log()
+        .iter()
+        .find(|log| log.reply_to() == Some(message_id))
+        .expect("No reply from ctoken");
+    if reply.reply_code().is_some_and(|code| code.is_success()) {
+        Ok(Decode::decode(&mut reply.payload()).expect("Unable to decode reply"))
+    } else {
+        Err(String::from_utf8_lossy(reply.payload()).into_owned())
+    }
+}
+
+pub fn state(sys: &System) -> CompoundState {
+    match read_state(sys, StateQuery::State) {
+        StateReply::State(state) => *state,
+        reply => panic!("Unexpected reply: {reply:?}"),
+    }
+}
+
+pub fn read_state(sys: &System, query: StateQuery) -> StateReply {
+    sys.get_program(COMPOUND)
+        .expect("Compound is not deployed")
+        .read_state(query)
+        .expect("Unable to read state")
+}
+
+pub fn user_assets(sys: &System, market: u64, user: u64) -> Assets {
+    compound_state::user_assets(&state(sys), &market.into(), &user.into())
+        .cloned()
+        .unwrap_or_default()
+}
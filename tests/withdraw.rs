@@ -0,0 +1,108 @@
+use compound_io::*;
+use gtest::System;
+use utils::*;
+
+mod utils;
+
+fn withdraw_action(market: u64, amount: u128) -> CompoundAction {
+    CompoundAction::WithdrawTokens {
+        market: market.into(),
+        amount: Tokens(amount),
+    }
+}
+
+#[test]
+fn withdraw_burns_ctokens() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+
+    assert_eq!(
+        send(&sys, LENDER, withdraw_action(TOKEN_A, 400)),
+        Ok(CompoundEvent::TokensWithdrawed {
+            market: TOKEN_A.into(),
+            address: LENDER.into(),
+            amount: Tokens(400),
+        })
+    );
+    assert_eq!(token_balance(&sys, TOKEN_A, LENDER), USER_BALANCE - 600);
+    assert_eq!(ctoken_balance(&sys, TOKEN_A, LENDER), 600);
+    assert_eq!(token_balance(&sys, TOKEN_A, COMPOUND), 600);
+    assert_eq!(user_assets(&sys, TOKEN_A, LENDER).lent_amount, CTokens(600));
+
+    send(&sys, LENDER, withdraw_action(TOKEN_A, 600)).expect("Withdraw failed");
+    assert_eq!(token_balance(&sys, TOKEN_A, LENDER), USER_BALANCE);
+    assert_eq!(user_assets(&sys, TOKEN_A, LENDER).lent_amount, CTokens(0));
+}
+
+#[test]
+fn withdraw_fails() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_A, 1_000);
+
+    assert_eq!(
+        send(&sys, LENDER, withdraw_action(TOKEN_A, 1_001)),
+        Err(CompoundError::AmountTooBig)
+    );
+    assert_eq!(
+        send(&sys, LENDER, withdraw_action(TOKEN_A, 0)),
+        Err(CompoundError::ZeroAmount)
+    );
+    assert_eq!(
+        send(&sys, BORROWER, withdraw_action(TOKEN_A, 1)),
+        Err(CompoundError::NoAssets(BORROWER.into()))
+    );
+    assert_eq!(
+        send(&sys, LENDER, withdraw_action(TOKEN_B, 1)),
+        Err(CompoundError::NoAssets(LENDER.into()))
+    );
+}
+
+#[test]
+fn withdraw_keeps_debt_collateralized() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_B, 10_000);
+    lend(&sys, BORROWER, TOKEN_A, 1_000);
+    borrow(&sys, BORROWER, TOKEN_B, 250);
+
+    // под 250 долга нужно 500 залога с коэффициентом 50%, остальное можно вывести
+    assert_eq!(
+        send(&sys, BORROWER, withdraw_action(TOKEN_A, 600)),
+        Err(CompoundError::InsufficientCollateral)
+    );
+    send(&sys, BORROWER, withdraw_action(TOKEN_A, 400)).expect("Withdraw failed");
+    assert_eq!(token_balance(&sys, TOKEN_A, BORROWER), USER_BALANCE - 600);
+}
+
+#[test]
+fn withdraw_after_interest_returns_more() {
+    let sys = System::new();
+    init(&sys);
+    lend(&sys, LENDER, TOKEN_B, 10_000);
+    lend(&sys, BORROWER, TOKEN_A, 100_000);
+    borrow(&sys, BORROWER, TOKEN_B, 5_000);
+    skip_seconds(&sys, 30 * 24 * 60 * 60 / 100);
+
+    // пока долг не погашен, свободных токенов меньше вклада
+    assert_eq!(
+        send(&sys, LENDER, withdraw_action(TOKEN_B, 10_000)),
+        Err(CompoundError::NotEnoughCash)
+    );
+    let debt = compound_state::borrow_amount(&state(&sys), &TOKEN_B.into(), &BORROWER.into());
+    send(
+        &sys,
+        BORROWER,
+        CompoundAction::RefundTokens {
+            market: TOKEN_B.into(),
+            amount: debt,
+        },
+    )
+    .expect("Refund failed");
+
+    let lent = compound_state::lent_amount(&state(&sys), &TOKEN_B.into(), &LENDER.into());
+    assert!(lent > Tokens(10_000));
+    send(&sys, LENDER, withdraw_action(TOKEN_B, lent.0 - 1)).expect("Withdraw failed");
+    assert!(token_balance(&sys, TOKEN_B, LENDER) > USER_BALANCE);
+}